@@ -0,0 +1,540 @@
+//! Self-balancing AVL tree map and set built on `BinaryTree`.
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+
+use crate::binary_tree::{BinaryNodeId, BinaryNodeRef, BinaryTree};
+use crate::bst::{self, entry, Keyed, Search};
+use crate::iter::Inorder;
+
+struct AvlNode<K, V> {
+    key: K,
+    value: V,
+    /// Number of nodes on the longest path from this node down to a leaf.
+    height: usize,
+}
+
+impl<K, V> Keyed for AvlNode<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn key(&self) -> &K {
+        &self.key
+    }
+
+    fn value(&self) -> &V {
+        &self.value
+    }
+}
+
+/// Ordered map backed by an AVL tree.
+///
+/// The heights of the two subtrees of every node differ by at most one, so
+/// lookups, insertions and removals take O(log n) time.
+pub struct AvlTreeMap<K, V> {
+    tree: Option<BinaryTree<AvlNode<K, V>>>,
+    len: usize,
+}
+
+impl<K: Ord, V> AvlTreeMap<K, V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self { tree: None, len: 0 }
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a key-value pair, returning the previous value for the key.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let node = AvlNode {
+            key,
+            value,
+            height: 1,
+        };
+        let tree = match &mut self.tree {
+            Some(tree) => tree,
+            None => {
+                self.tree = Some(BinaryTree::new(node));
+                self.len = 1;
+                return None;
+            }
+        };
+
+        match bst::search(tree, &node.key) {
+            Search::Found(id) => {
+                let mut existing = tree.get_mut(id).expect("exists");
+                Some(std::mem::replace(&mut existing.value().value, node.value))
+            }
+            Search::Vacant(parent_id, ordering) => {
+                let mut parent = tree.get_mut(parent_id).expect("exists");
+                if ordering == Ordering::Less {
+                    parent.set_left(node);
+                } else {
+                    parent.set_right(node);
+                }
+                self.len += 1;
+                rebalance_from(tree, Some(parent_id));
+                None
+            }
+        }
+    }
+
+    /// Returns a reference to the value for the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let tree = self.tree.as_ref()?;
+        match bst::search(tree, key) {
+            Search::Found(id) => tree.get(id).map(|node| &node.value().value),
+            Search::Vacant(..) => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value for the key.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let tree = self.tree.as_mut()?;
+        match bst::search(tree, key) {
+            Search::Found(id) => tree.get_mut(id).map(|node| &mut node.into_value().value),
+            Search::Vacant(..) => None,
+        }
+    }
+
+    /// Returns true if the map contains the key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Removes the key, returning its value if it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_key, value)| value)
+    }
+
+    /// Removes the key, returning the stored key and value if it was present.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let tree = self.tree.as_mut()?;
+        let id = match bst::search(tree, key) {
+            Search::Found(id) => id,
+            Search::Vacant(..) => return None,
+        };
+
+        self.len -= 1;
+        if self.len == 0 {
+            let node = self.tree.take().expect("exists").into_root_value();
+            return Some((node.key, node.value));
+        }
+
+        // With two children, the in-order successor (which has no left child)
+        // is spliced out instead and its entry moved into the removed node.
+        let node = tree.get(id).expect("exists");
+        let spliced_id = match (node.left(), node.right()) {
+            (Some(_), Some(right)) => bst::leftmost(right).id(),
+            _ => id,
+        };
+        let parent_id = tree
+            .get(spliced_id)
+            .and_then(|spliced| spliced.parent())
+            .map(|parent| parent.id());
+
+        let spliced = tree.get_mut(spliced_id).expect("exists").splice_out();
+        let removed = if spliced_id == id {
+            (spliced.key, spliced.value)
+        } else {
+            let mut node = tree.get_mut(id).expect("exists");
+            let node = node.value();
+            (
+                std::mem::replace(&mut node.key, spliced.key),
+                std::mem::replace(&mut node.value, spliced.value),
+            )
+        };
+
+        rebalance_from(tree, parent_id);
+        Some(removed)
+    }
+
+    /// Returns the entry with the smallest key.
+    pub fn min(&self) -> Option<(&K, &V)> {
+        self.tree
+            .as_ref()
+            .map(|tree| entry(bst::leftmost(tree.root())))
+    }
+
+    /// Returns the entry with the largest key.
+    pub fn max(&self) -> Option<(&K, &V)> {
+        self.tree
+            .as_ref()
+            .map(|tree| entry(bst::rightmost(tree.root())))
+    }
+
+    /// Returns the entry with the largest key less than or equal to `key`.
+    pub fn floor<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        bst::floor(self.tree.as_ref()?, key).map(entry)
+    }
+
+    /// Returns the entry with the smallest key greater than or equal to `key`.
+    pub fn ceiling<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        bst::ceiling(self.tree.as_ref()?, key).map(entry)
+    }
+
+    /// Returns an iterator over the entries in ascending key order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.tree.as_ref().map(BinaryTree::inorder),
+        }
+    }
+
+    /// Checks that keys are in ascending order, that the stored heights are
+    /// accurate and that every node is balanced, describing the first
+    /// violation found.
+    pub fn validate(&self) -> Result<(), String> {
+        bst::validate_order(self.tree.as_ref(), self.len)?;
+        let tree = match &self.tree {
+            Some(tree) => tree,
+            None => return Ok(()),
+        };
+
+        for node in tree.preorder() {
+            let (left, right) = (height(node.left()), height(node.right()));
+            if node.value().height != 1 + left.max(right) {
+                return Err(format!("stale height at depth {}", node.depth()));
+            }
+            if left.max(right) - left.min(right) > 1 {
+                return Err(format!("unbalanced node at depth {}", node.depth()));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<K: Ord, V> Default for AvlTreeMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, K: Ord, V> IntoIterator for &'a AvlTreeMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+fn height<K, V>(node: Option<BinaryNodeRef<'_, AvlNode<K, V>>>) -> usize {
+    node.map_or(0, |node| node.value().height)
+}
+
+fn update_height<K, V>(tree: &mut BinaryTree<AvlNode<K, V>>, id: BinaryNodeId) {
+    let node = tree.get(id).expect("exists");
+    let height = 1 + height(node.left()).max(height(node.right()));
+    tree.get_mut(id).expect("exists").value().height = height;
+}
+
+/// Left height minus right height.
+fn balance_factor<K, V>(tree: &BinaryTree<AvlNode<K, V>>, id: BinaryNodeId) -> isize {
+    let node = tree.get(id).expect("exists");
+    height(node.left()) as isize - height(node.right()) as isize
+}
+
+fn left_id<T>(tree: &BinaryTree<T>, id: BinaryNodeId) -> BinaryNodeId {
+    tree.get(id)
+        .and_then(|node| node.left())
+        .expect("exists")
+        .id()
+}
+
+fn right_id<T>(tree: &BinaryTree<T>, id: BinaryNodeId) -> BinaryNodeId {
+    tree.get(id)
+        .and_then(|node| node.right())
+        .expect("exists")
+        .id()
+}
+
+/// Restores the AVL invariant at the node with the given id, assuming both of
+/// its subtrees are balanced. Returns the id of the node now in its place.
+fn rebalance<K, V>(tree: &mut BinaryTree<AvlNode<K, V>>, id: BinaryNodeId) -> BinaryNodeId {
+    update_height(tree, id);
+    let balance = balance_factor(tree, id);
+    if balance > 1 {
+        let left = left_id(tree, id);
+        if balance_factor(tree, left) < 0 {
+            tree.get_mut(left).expect("exists").rotate_left();
+            update_height(tree, left);
+            update_height(tree, left_id(tree, id));
+        }
+        let pivot = left_id(tree, id);
+        tree.get_mut(id).expect("exists").rotate_right();
+        update_height(tree, id);
+        update_height(tree, pivot);
+        pivot
+    } else if balance < -1 {
+        let right = right_id(tree, id);
+        if balance_factor(tree, right) > 0 {
+            tree.get_mut(right).expect("exists").rotate_right();
+            update_height(tree, right);
+            update_height(tree, right_id(tree, id));
+        }
+        let pivot = right_id(tree, id);
+        tree.get_mut(id).expect("exists").rotate_left();
+        update_height(tree, id);
+        update_height(tree, pivot);
+        pivot
+    } else {
+        id
+    }
+}
+
+/// Rebalances every node from `start` up to the root.
+fn rebalance_from<K, V>(tree: &mut BinaryTree<AvlNode<K, V>>, start: Option<BinaryNodeId>) {
+    let mut next = start;
+    while let Some(id) = next {
+        let top = rebalance(tree, id);
+        next = tree
+            .get(top)
+            .and_then(|node| node.parent())
+            .map(|parent| parent.id());
+    }
+}
+
+/// Iterator over the entries of an `AvlTreeMap` in ascending key order.
+pub struct Iter<'a, K, V> {
+    inner: Option<Inorder<'a, AvlNode<K, V>>>,
+}
+
+impl<'a, K, V> Clone for Iter<'a, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.as_mut()?.next().map(entry)
+    }
+}
+
+/// Ordered set backed by an AVL tree.
+pub struct AvlTreeSet<K> {
+    map: AvlTreeMap<K, ()>,
+}
+
+impl<K: Ord> AvlTreeSet<K> {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self {
+            map: AvlTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of keys in the set.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if the set contains no keys.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Adds a key to the set, returning true if it was not already present.
+    pub fn insert(&mut self, key: K) -> bool {
+        self.map.insert(key, ()).is_none()
+    }
+
+    /// Returns true if the set contains the key.
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Removes a key from the set, returning true if it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove(key).is_some()
+    }
+
+    /// Returns the smallest key.
+    pub fn min(&self) -> Option<&K> {
+        self.map.min().map(|(key, _)| key)
+    }
+
+    /// Returns the largest key.
+    pub fn max(&self) -> Option<&K> {
+        self.map.max().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the keys in ascending order.
+    pub fn iter(&self) -> SetIter<'_, K> {
+        SetIter {
+            inner: self.map.iter(),
+        }
+    }
+
+    /// Checks the AVL invariants, see `AvlTreeMap::validate`.
+    pub fn validate(&self) -> Result<(), String> {
+        self.map.validate()
+    }
+}
+
+impl<K: Ord> Default for AvlTreeSet<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, K: Ord> IntoIterator for &'a AvlTreeSet<K> {
+    type Item = &'a K;
+    type IntoIter = SetIter<'a, K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the keys of an `AvlTreeSet` in ascending order.
+pub struct SetIter<'a, K> {
+    inner: Iter<'a, K, ()>,
+}
+
+impl<'a, K> Clone for SetIter<'a, K> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K> Iterator for SetIter<'a, K> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorted_inserts_stay_balanced() {
+        let mut map = AvlTreeMap::new();
+        for key in 0..1000 {
+            assert_eq!(map.insert(key, key * 2), None);
+            map.validate().unwrap();
+        }
+
+        assert_eq!(map.len(), 1000);
+        assert!(map.tree.as_ref().unwrap().height() < 15);
+        assert_eq!(map.get(&500), Some(&1000));
+        assert_eq!(map.insert(500, 0), Some(1000));
+        *map.get_mut(&500).unwrap() += 1;
+        assert_eq!(map.get(&500), Some(&1));
+        assert_eq!(map.min(), Some((&0, &0)));
+        assert_eq!(map.max(), Some((&999, &1998)));
+        assert!(map.iter().map(|(key, _)| *key).eq(0..1000));
+    }
+
+    #[test]
+    fn removals_stay_balanced() {
+        let mut map = AvlTreeMap::new();
+        // Insert in a scrambled but deterministic order.
+        for i in 0..512 {
+            let key = (i * 167) % 512;
+            map.insert(key, ());
+        }
+        map.validate().unwrap();
+
+        for key in (0..512).filter(|key| key % 3 != 0) {
+            assert_eq!(map.remove(&key), Some(()));
+            map.validate().unwrap();
+        }
+        assert_eq!(map.remove(&1), None);
+        assert!(map.iter().map(|(key, _)| *key).eq((0..512).step_by(3)));
+
+        for key in (0..512).step_by(3) {
+            map.remove(&key);
+            map.validate().unwrap();
+        }
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn churn_does_not_grow_arena() {
+        let mut map = AvlTreeMap::new();
+        bst::check_churn(
+            &mut map,
+            |map, key| {
+                map.insert(key, ());
+            },
+            |map, key| {
+                map.remove(&key);
+            },
+            |map| map.tree.as_ref().unwrap().arena_len(),
+        );
+        map.validate().unwrap();
+        assert_eq!(map.len(), 16);
+    }
+
+    #[test]
+    fn set_works() {
+        let mut set = AvlTreeSet::new();
+        assert!(set.insert("b"));
+        assert!(set.insert("a"));
+        assert!(set.insert("c"));
+        assert!(!set.insert("a"));
+        assert!(set.contains("a"));
+        assert!(set.remove("a"));
+        assert!(!set.remove("a"));
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), vec!["b", "c"]);
+        let mut iter = set.iter();
+        assert_eq!(iter.next(), Some(&"b"));
+        assert_eq!(iter.clone().collect::<Vec<_>>(), vec![&"c"]);
+        assert_eq!(set.min(), Some(&"b"));
+        set.validate().unwrap();
+    }
+}
@@ -1,135 +1,912 @@
-use ego_tree::{NodeMut, NodeRef, Tree};
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+use ego_tree::{NodeId, NodeMut, NodeRef, Tree};
+
+use crate::error::BinaryTreeError;
+use crate::iter::{
+    Ancestors, Inorder, LevelOrder, LevelOrderWithDepth, Levels, Postorder, Preorder, ValuesMut,
+};
 
 /// Wrapper around a ego_tree::Tree that constrains functionality / API
 /// to a binary tree. Always contains at least one node.
+///
+/// Every node in the tree has exactly two children in the inner tree: the
+/// left and right slot. Empty slots hold a `None` placeholder without any
+/// children.
+///
+/// Nodes removed from the tree are detached, emptied and put on a free list,
+/// and new nodes reuse them before the arena grows, so the arena is bounded
+/// by the largest size the tree ever had rather than by the number of
+/// mutations. Every node counts how often it was emptied and ids carry that
+/// generation, so the id of a removed node isn't found again when its storage
+/// is reused.
+///
+/// The root of the inner tree is a `None` placeholder whose only child is the
+/// root of the binary tree, so that the binary root can be replaced like any
+/// other node.
+///
+/// Clones keep the layout of the inner tree, so ids handed out by a tree also
+/// refer to the same nodes of its clones.
+#[derive(Clone)]
 pub struct BinaryTree<T> {
-    inner: Tree<Option<T>>,
+    inner: Tree<NodeData<T>>,
+    /// Detached, childless nodes which are reused for new nodes.
+    free: Vec<NodeId>,
+}
+
+/// Value of a node in the inner tree.
+#[derive(Debug, Clone)]
+struct NodeData<T> {
+    /// Value of the binary tree node, `None` for empty slots.
+    value: Option<T>,
+    /// Bumped whenever the node is emptied. Wide enough that it never wraps
+    /// around in practice, so stale ids can't match a reused node.
+    generation: u64,
+}
+
+impl<T> NodeData<T> {
+    fn empty() -> Self {
+        Self {
+            value: None,
+            generation: 0,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.value.is_none()
+    }
 }
 
 impl<T> BinaryTree<T> {
     pub fn new(root_value: T) -> Self {
-        let mut tree = Tree::new(Some(root_value));
-        let mut root = tree.root_mut();
-        root.append(None);
-        root.append(None);
-        Self { inner: tree }
+        let mut inner = Tree::new(NodeData::empty());
+        let mut free = Vec::new();
+        let root_id = inner.root_mut().append(NodeData::empty()).id();
+        fill(&mut inner, &mut free, root_id, root_value);
+        Self { inner, free }
+    }
+
+    fn root_id(&self) -> NodeId {
+        self.inner.root().first_child().expect("exists").id()
+    }
+
+    pub(crate) fn into_root_value(mut self) -> T {
+        self.root_mut().inner.value().value.take().expect("exists")
+    }
+
+    /// Moves the value out of the node with the given id. The node is no
+    /// longer found afterwards while its children still are, so this is only
+    /// for taking a tree apart before dropping it.
+    pub(crate) fn take_value(&mut self, id: BinaryNodeId) -> T {
+        let mut node = self.inner.get_mut(id.0).expect("exists");
+        node.value().value.take().expect("exists")
+    }
+
+    /// Returns the number of nodes in the arena, including empty slots and
+    /// freed nodes.
+    #[cfg(test)]
+    pub(crate) fn arena_len(&self) -> usize {
+        self.inner.nodes().count()
     }
 
     /// Returns a reference to the root node.
-    pub fn root(&self) -> BinaryNodeRef<T> {
-        BinaryNodeRef::wrap(self.inner.root())
+    pub fn root(&self) -> BinaryNodeRef<'_, T> {
+        BinaryNodeRef::wrap(self.inner.root().first_child().expect("exists"))
     }
 
     /// Returns a mutator of the root node.
-    pub fn root_mut(&mut self) -> BinaryNodeMut<T> {
-        BinaryNodeMut::wrap(self.inner.root_mut())
+    pub fn root_mut(&mut self) -> BinaryNodeMut<'_, T> {
+        let root_id = self.root_id();
+        BinaryNodeMut::wrap(self.inner.get_mut(root_id).expect("exists"), &mut self.free)
     }
+
+    /// Returns a reference to the node with the given id, if it is in the tree.
+    ///
+    /// Ids are only meaningful for the tree that handed them out; looking up
+    /// an id from another tree returns an arbitrary node or `None`.
+    pub fn get(&self, id: BinaryNodeId) -> Option<BinaryNodeRef<'_, T>> {
+        let node = self.inner.get(id.0)?;
+        if node.value().is_empty() || node.value().generation != id.1 {
+            return None;
+        }
+
+        Some(BinaryNodeRef::wrap(node))
+    }
+
+    /// Returns a mutator of the node with the given id, if it is in the tree.
+    pub fn get_mut(&mut self, id: BinaryNodeId) -> Option<BinaryNodeMut<'_, T>> {
+        self.get(id)?;
+        let node = self.inner.get_mut(id.0).expect("exists");
+        Some(BinaryNodeMut::wrap(node, &mut self.free))
+    }
+
+    /// Like `get`, but fails with `BinaryTreeError::NotFound`.
+    pub fn try_get(&self, id: BinaryNodeId) -> Result<BinaryNodeRef<'_, T>, BinaryTreeError> {
+        self.get(id).ok_or(BinaryTreeError::NotFound(id))
+    }
+
+    /// Like `get_mut`, but fails with `BinaryTreeError::NotFound`.
+    pub fn try_get_mut(
+        &mut self,
+        id: BinaryNodeId,
+    ) -> Result<BinaryNodeMut<'_, T>, BinaryTreeError> {
+        self.get_mut(id).ok_or(BinaryTreeError::NotFound(id))
+    }
+
+    /// Returns an iterator over the nodes of the tree in pre-order.
+    pub fn preorder(&self) -> Preorder<'_, T> {
+        self.root().preorder()
+    }
+
+    /// Returns an iterator over the nodes of the tree in in-order.
+    pub fn inorder(&self) -> Inorder<'_, T> {
+        self.root().inorder()
+    }
+
+    /// Returns an iterator over the nodes of the tree in post-order.
+    pub fn postorder(&self) -> Postorder<'_, T> {
+        self.root().postorder()
+    }
+
+    /// Returns an iterator over the nodes of the tree in level-order.
+    pub fn level_order(&self) -> LevelOrder<'_, T> {
+        self.root().level_order()
+    }
+
+    /// Returns an iterator over the nodes of the tree in level-order, paired
+    /// with their depth (the root has depth 0).
+    pub fn level_order_with_depth(&self) -> LevelOrderWithDepth<'_, T> {
+        self.root().level_order_with_depth()
+    }
+
+    /// Returns an iterator over the levels of the tree, starting at the root.
+    pub fn levels(&self) -> Levels<'_, T> {
+        self.root().levels()
+    }
+
+    /// Returns an iterator over mutable references to the values of the tree
+    /// in pre-order.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, T> {
+        self.preorder_values_mut()
+    }
+
+    /// Returns an iterator over mutable references to the values of the tree
+    /// in pre-order.
+    pub fn preorder_values_mut(&mut self) -> ValuesMut<'_, T> {
+        let order: Vec<NodeId> = self.preorder().map(|node| node.inner.id()).collect();
+        self.values_mut_in(order)
+    }
+
+    /// Returns an iterator over mutable references to the values of the tree
+    /// in in-order.
+    pub fn inorder_values_mut(&mut self) -> ValuesMut<'_, T> {
+        let order: Vec<NodeId> = self.inorder().map(|node| node.inner.id()).collect();
+        self.values_mut_in(order)
+    }
+
+    /// Returns an iterator over mutable references to the values of the tree
+    /// in post-order.
+    pub fn postorder_values_mut(&mut self) -> ValuesMut<'_, T> {
+        let order: Vec<NodeId> = self.postorder().map(|node| node.inner.id()).collect();
+        self.values_mut_in(order)
+    }
+
+    /// Returns an iterator over mutable references to the values of the tree
+    /// in level-order.
+    pub fn level_order_values_mut(&mut self) -> ValuesMut<'_, T> {
+        let order: Vec<NodeId> = self.level_order().map(|node| node.inner.id()).collect();
+        self.values_mut_in(order)
+    }
+
+    /// Hands out the values of the given nodes, in order. The arena is walked
+    /// once to split it into disjoint mutable references, which are then
+    /// picked by position, so no node may be given twice. Freed nodes are
+    /// reused, so the arena is bounded by the largest size the tree had.
+    fn values_mut_in(&mut self, order: Vec<NodeId>) -> ValuesMut<'_, T> {
+        let positions: HashMap<NodeId, usize> = self
+            .inner
+            .nodes()
+            .enumerate()
+            .map(|(position, node)| (node.id(), position))
+            .collect();
+        let mut slots: Vec<Option<&mut NodeData<T>>> = self.inner.values_mut().map(Some).collect();
+
+        let values = order
+            .into_iter()
+            .map(|id| {
+                let slot = slots[positions[&id]].take().expect("visited once");
+                slot.value.as_mut().expect("exists")
+            })
+            .collect();
+        ValuesMut::new(values)
+    }
+}
+
+/// Tree with only a root holding `T::default()`.
+impl<T: Default> Default for BinaryTree<T> {
+    fn default() -> Self {
+        BinaryTree::new(T::default())
+    }
+}
+
+/// Identifier of a node in a `BinaryTree`.
+///
+/// Unlike `BinaryNodeRef` / `BinaryNodeMut`, ids do not borrow the tree and
+/// stay valid while the tree is mutated. Use `BinaryTree::get` and
+/// `BinaryTree::get_mut` to access the node again. Once the node is removed
+/// its id is never found again, even if its storage is reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BinaryNodeId(NodeId, u64);
+
+/// One of the two child slots of a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Left,
+    Right,
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+impl Display for Side {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Side::Left => f.write_str("left"),
+            Side::Right => f.write_str("right"),
+        }
+    }
+}
+
+#[derive(Debug)]
 pub struct BinaryNodeRef<'a, T> {
-    inner: NodeRef<'a, Option<T>>,
+    inner: NodeRef<'a, NodeData<T>>,
+}
+
+// Implemented by hand so that node references are copyable and comparable
+// (by identity) regardless of T.
+impl<'a, T> Copy for BinaryNodeRef<'a, T> {}
+impl<'a, T> Clone for BinaryNodeRef<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Eq for BinaryNodeRef<'a, T> {}
+impl<'a, T> PartialEq for BinaryNodeRef<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
 }
 
 impl<'a, T> BinaryNodeRef<'a, T> {
     /// Return the left child, if exists.
     pub fn left(&self) -> Option<BinaryNodeRef<'a, T>> {
-        let left = self.inner.children().next().expect("always has children");
-        if left.value().is_none() {
-            return None;
-        }
-
-        Some(BinaryNodeRef::wrap(left))
+        self.try_left().expect("always has children")
     }
 
     /// Return the right child, if exists.
     pub fn right(&self) -> Option<BinaryNodeRef<'a, T>> {
-        let mut children = self.inner.children();
-        let _left = children.next().expect("always has children");
+        self.try_right().expect("always has children")
+    }
 
-        let right = children.next().expect("always has children");
-        if right.value().is_none() {
-            return None;
+    /// Like `left`, but fails with `BinaryTreeError::Corrupted` instead of
+    /// panicking if the node doesn't have its child slots.
+    pub fn try_left(&self) -> Result<Option<BinaryNodeRef<'a, T>>, BinaryTreeError> {
+        self.try_child(Side::Left)
+    }
+
+    /// Like `right`, but fails with `BinaryTreeError::Corrupted` instead of
+    /// panicking if the node doesn't have its child slots.
+    pub fn try_right(&self) -> Result<Option<BinaryNodeRef<'a, T>>, BinaryTreeError> {
+        self.try_child(Side::Right)
+    }
+
+    fn try_child(&self, side: Side) -> Result<Option<BinaryNodeRef<'a, T>>, BinaryTreeError> {
+        let slot = slot(self.inner, side)?;
+        if slot.value().is_empty() {
+            return Ok(None);
         }
 
-        Some(BinaryNodeRef::wrap(right))
+        Ok(Some(BinaryNodeRef::wrap(slot)))
+    }
+
+    /// Returns the id of this node.
+    pub fn id(&self) -> BinaryNodeId {
+        BinaryNodeId(self.inner.id(), self.inner.value().generation)
     }
 
     /// Get the value for this node.
-    pub fn value(&self) -> &T {
-        self.inner.value().as_ref().expect("exists")
+    pub fn value(&self) -> &'a T {
+        self.inner.value().value.as_ref().expect("exists")
     }
 
-    fn wrap(node: NodeRef<'a, Option<T>>) -> Self {
+    /// Return the parent, or `None` for the root.
+    pub fn parent(&self) -> Option<BinaryNodeRef<'a, T>> {
+        let parent = self.inner.parent().expect("always has parent");
+        // The parent of the root is the placeholder root of the inner tree.
+        if parent.value().is_empty() {
+            return None;
+        }
+
+        Some(BinaryNodeRef::wrap(parent))
+    }
+
+    /// Return the other child of this node's parent, if exists.
+    pub fn sibling(&self) -> Option<BinaryNodeRef<'a, T>> {
+        let sibling = self
+            .inner
+            .prev_sibling()
+            .or_else(|| self.inner.next_sibling())?;
+        if sibling.value().is_empty() {
+            return None;
+        }
+
+        Some(BinaryNodeRef::wrap(sibling))
+    }
+
+    /// Returns true if this node is the root of the tree.
+    pub fn is_root(&self) -> bool {
+        self.parent().is_none()
+    }
+
+    /// Returns true if this node is the left child of its parent.
+    pub fn is_left_child(&self) -> bool {
+        !self.is_root() && self.inner.prev_sibling().is_none()
+    }
+
+    /// Returns true if this node is the right child of its parent.
+    pub fn is_right_child(&self) -> bool {
+        !self.is_root() && self.inner.next_sibling().is_none()
+    }
+
+    /// Returns an iterator over the ancestors of this node, starting with its
+    /// parent and ending with the root.
+    pub fn ancestors(&self) -> Ancestors<'a, T> {
+        Ancestors::new(self.parent())
+    }
+
+    /// Returns an iterator over this subtree in pre-order (node, left, right).
+    pub fn preorder(&self) -> Preorder<'a, T> {
+        Preorder::new(*self)
+    }
+
+    /// Returns an iterator over this subtree in in-order (left, node, right).
+    pub fn inorder(&self) -> Inorder<'a, T> {
+        Inorder::new(*self)
+    }
+
+    /// Returns an iterator over this subtree in post-order (left, right, node).
+    pub fn postorder(&self) -> Postorder<'a, T> {
+        Postorder::new(*self)
+    }
+
+    /// Returns an iterator over this subtree in level-order (breadth-first).
+    pub fn level_order(&self) -> LevelOrder<'a, T> {
+        LevelOrder::new(*self)
+    }
+
+    /// Returns an iterator over this subtree in level-order, paired with the
+    /// depth relative to this node (this node has depth 0).
+    pub fn level_order_with_depth(&self) -> LevelOrderWithDepth<'a, T> {
+        LevelOrderWithDepth::new(*self)
+    }
+
+    /// Returns an iterator over the levels of this subtree, starting with a
+    /// level containing only this node.
+    pub fn levels(&self) -> Levels<'a, T> {
+        Levels::new(*self)
+    }
+
+    fn wrap(node: NodeRef<'a, NodeData<T>>) -> Self {
         Self { inner: node }
     }
 }
 
 #[derive(Debug)]
 pub struct BinaryNodeMut<'a, T> {
-    inner: NodeMut<'a, Option<T>>,
+    inner: NodeMut<'a, NodeData<T>>,
+    free: &'a mut Vec<NodeId>,
+    /// Generation of the node, which doesn't change while it's borrowed.
+    generation: u64,
 }
 
 impl<'a, T> BinaryNodeMut<'a, T> {
-    fn left_inner(&mut self) -> NodeMut<Option<T>> {
-        self.inner.first_child().expect("exists")
+    fn slot_id(&mut self, side: Side) -> NodeId {
+        self.try_slot(side).expect("always has children")
     }
 
-    fn right_inner(&mut self) -> NodeMut<Option<T>> {
-        self.inner.last_child().expect("exists")
+    fn try_slot(&mut self, side: Side) -> Result<NodeId, BinaryTreeError> {
+        let id = self.inner.id();
+        let tree = self.inner.tree();
+        Ok(slot(tree.get(id).expect("exists"), side)?.id())
     }
 
-    /// Return the left child, if exists.
-    pub fn left(&mut self) -> Option<BinaryNodeMut<T>> {
-        let mut left_inner = self.left_inner();
-        if left_inner.value().is_none() {
-            return None;
-        }
+    /// Returns a mutator of the node with the given id, which may be an
+    /// empty slot.
+    fn node(&mut self, id: NodeId) -> BinaryNodeMut<'_, T> {
+        let node = self.inner.tree().get_mut(id).expect("exists");
+        BinaryNodeMut::wrap(node, self.free)
+    }
 
-        Some(BinaryNodeMut::wrap(left_inner))
+    /// Return the left child, if exists.
+    pub fn left(&mut self) -> Option<BinaryNodeMut<'_, T>> {
+        self.try_left().expect("always has children")
     }
 
     /// Return the right child, if exists.
-    pub fn right(&mut self) -> Option<BinaryNodeMut<T>> {
-        let mut right_inner = self.right_inner();
-        if right_inner.value().is_none() {
-            return None;
+    pub fn right(&mut self) -> Option<BinaryNodeMut<'_, T>> {
+        self.try_right().expect("always has children")
+    }
+
+    /// Like `left`, but fails with `BinaryTreeError::Corrupted` instead of
+    /// panicking if the node doesn't have its child slots.
+    pub fn try_left(&mut self) -> Result<Option<BinaryNodeMut<'_, T>>, BinaryTreeError> {
+        self.try_child(Side::Left)
+    }
+
+    /// Like `right`, but fails with `BinaryTreeError::Corrupted` instead of
+    /// panicking if the node doesn't have its child slots.
+    pub fn try_right(&mut self) -> Result<Option<BinaryNodeMut<'_, T>>, BinaryTreeError> {
+        self.try_child(Side::Right)
+    }
+
+    fn try_child(&mut self, side: Side) -> Result<Option<BinaryNodeMut<'_, T>>, BinaryTreeError> {
+        let slot_id = self.try_slot(side)?;
+        let mut slot = self.node(slot_id);
+        if slot.inner.value().is_empty() {
+            return Ok(None);
         }
 
-        Some(BinaryNodeMut::wrap(right_inner))
+        Ok(Some(slot))
+    }
+
+    /// Returns the id of this node.
+    pub fn id(&self) -> BinaryNodeId {
+        BinaryNodeId(self.inner.id(), self.generation)
     }
 
     /// Get the value for this node.
     pub fn value(&mut self) -> &mut T {
-        self.inner.value().as_mut().expect("exists")
+        self.inner.value().value.as_mut().expect("exists")
+    }
+
+    /// Convert into a mutable reference to the value of this node, for as
+    /// long as the tree is borrowed.
+    pub fn into_value(mut self) -> &'a mut T {
+        let value: *mut T = self.value();
+        // SAFETY: `self` holds the exclusive borrow of the tree for `'a` and is
+        // consumed here, so nothing else can access the node during `'a`.
+        unsafe { &mut *value }
     }
 
     /// Set the right child to value and return the node.
-    pub fn set_right(&mut self, value: T) -> BinaryNodeMut<T> {
-        let mut right_inner = self.right_inner();
-        *right_inner.value() = Some(value);
-        // Create left / right nodes for new node if not present.
-        if !right_inner.has_children() {
-            right_inner.append(None);
-            right_inner.append(None);
-        }
-        BinaryNodeMut::wrap(right_inner)
+    ///
+    /// An existing right child has its value overwritten and keeps its
+    /// subtree, see `insert_right` / `replace_right` for alternatives.
+    pub fn set_right(&mut self, value: T) -> BinaryNodeMut<'_, T> {
+        let right_id = self.slot_id(Side::Right);
+        fill(self.inner.tree(), self.free, right_id, value);
+        self.node(right_id)
     }
 
     /// Set the left child to value and return the node.
-    pub fn set_left(&mut self, value: T) -> BinaryNodeMut<T> {
-        let mut left_inner = self.left_inner();
-        *left_inner.value() = Some(value);
-        // Create left / right nodes for new node if not present.
-        if !left_inner.has_children() {
-            left_inner.append(None);
-            left_inner.append(None);
+    ///
+    /// An existing left child has its value overwritten and keeps its
+    /// subtree, see `insert_left` / `replace_left` for alternatives.
+    pub fn set_left(&mut self, value: T) -> BinaryNodeMut<'_, T> {
+        let left_id = self.slot_id(Side::Left);
+        fill(self.inner.tree(), self.free, left_id, value);
+        self.node(left_id)
+    }
+
+    /// Like `set_left`, but fails with `BinaryTreeError::Occupied` instead of
+    /// overwriting an existing left child. `value` is given back with the
+    /// error.
+    pub fn try_set_left(&mut self, value: T) -> Result<BinaryNodeMut<'_, T>, (BinaryTreeError, T)> {
+        self.try_fill(Side::Left, value)
+    }
+
+    /// Like `set_right`, but fails with `BinaryTreeError::Occupied` instead of
+    /// overwriting an existing right child. `value` is given back with the
+    /// error.
+    pub fn try_set_right(
+        &mut self,
+        value: T,
+    ) -> Result<BinaryNodeMut<'_, T>, (BinaryTreeError, T)> {
+        self.try_fill(Side::Right, value)
+    }
+
+    fn try_fill(
+        &mut self,
+        side: Side,
+        value: T,
+    ) -> Result<BinaryNodeMut<'_, T>, (BinaryTreeError, T)> {
+        let slot_id = match self.try_slot(side) {
+            Ok(slot_id) => slot_id,
+            Err(error) => return Err((error, value)),
+        };
+        let mut slot = self.node(slot_id);
+        if !slot.inner.value().is_empty() {
+            return Err((BinaryTreeError::Occupied(side), value));
         }
-        BinaryNodeMut::wrap(left_inner)
+
+        fill(slot.inner.tree(), slot.free, slot_id, value);
+        Ok(slot)
     }
 
-    fn wrap(node: NodeMut<'a, Option<T>>) -> Self {
-        Self { inner: node }
+    /// Remove the left subtree, returning the value of the left child.
+    pub fn remove_left(&mut self) -> Option<T> {
+        self.remove_child(Side::Left)
+    }
+
+    /// Remove the right subtree, returning the value of the right child.
+    pub fn remove_right(&mut self) -> Option<T> {
+        self.remove_child(Side::Right)
+    }
+
+    /// Detach the left subtree and return it as a standalone tree.
+    pub fn take_left(&mut self) -> Option<BinaryTree<T>> {
+        self.take_child(Side::Left)
+    }
+
+    /// Detach the right subtree and return it as a standalone tree.
+    pub fn take_right(&mut self) -> Option<BinaryTree<T>> {
+        self.take_child(Side::Right)
+    }
+
+    /// Move `subtree` into the left slot, returning the previous left subtree.
+    pub fn attach_left(&mut self, subtree: BinaryTree<T>) -> Option<BinaryTree<T>> {
+        let previous = self.take_left();
+        let left_id = self.slot_id(Side::Left);
+        self.graft(left_id, subtree);
+        previous
+    }
+
+    /// Move `subtree` into the right slot, returning the previous right subtree.
+    pub fn attach_right(&mut self, subtree: BinaryTree<T>) -> Option<BinaryTree<T>> {
+        let previous = self.take_right();
+        let right_id = self.slot_id(Side::Right);
+        self.graft(right_id, subtree);
+        previous
+    }
+
+    /// Rotate this node down to the left, so that its right child takes its
+    /// place and this node becomes the left child of its former right child.
+    /// The left subtree of the right child becomes this node's right subtree.
+    ///
+    /// This node's mutator keeps pointing at the same node after the rotation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node has no right child.
+    pub fn rotate_left(&mut self) {
+        self.try_rotate_left()
+            .expect("rotate_left requires a right child")
+    }
+
+    /// Like `rotate_left`, but fails with `BinaryTreeError::MissingChild`
+    /// instead of panicking if the node has no right child. The tree is left
+    /// unchanged on failure.
+    pub fn try_rotate_left(&mut self) -> Result<(), BinaryTreeError> {
+        let mut pivot = self
+            .try_right()?
+            .ok_or(BinaryTreeError::MissingChild(Side::Right))?;
+        let pivot_id = pivot.inner.id();
+        let pivot_left_id = pivot.try_slot(Side::Left)?;
+        let id = self.inner.id();
+
+        // Move the pivot into this node's slot.
+        self.inner.insert_id_before(pivot_id);
+        self.inner.detach();
+
+        self.inner.append_id(pivot_left_id);
+        self.inner
+            .tree()
+            .get_mut(pivot_id)
+            .expect("exists")
+            .prepend_id(id);
+        Ok(())
+    }
+
+    /// Rotate this node down to the right, so that its left child takes its
+    /// place and this node becomes the right child of its former left child.
+    /// The right subtree of the left child becomes this node's left subtree.
+    ///
+    /// This node's mutator keeps pointing at the same node after the rotation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node has no left child.
+    pub fn rotate_right(&mut self) {
+        self.try_rotate_right()
+            .expect("rotate_right requires a left child")
+    }
+
+    /// Like `rotate_right`, but fails with `BinaryTreeError::MissingChild`
+    /// instead of panicking if the node has no left child. The tree is left
+    /// unchanged on failure.
+    pub fn try_rotate_right(&mut self) -> Result<(), BinaryTreeError> {
+        let mut pivot = self
+            .try_left()?
+            .ok_or(BinaryTreeError::MissingChild(Side::Left))?;
+        let pivot_id = pivot.inner.id();
+        let pivot_right_id = pivot.try_slot(Side::Right)?;
+        let id = self.inner.id();
+
+        // Move the pivot into this node's slot.
+        self.inner.insert_id_before(pivot_id);
+        self.inner.detach();
+
+        self.inner.prepend_id(pivot_right_id);
+        self.inner
+            .tree()
+            .get_mut(pivot_id)
+            .expect("exists")
+            .append_id(id);
+        Ok(())
+    }
+
+    /// Rotate the left child to the left, then this node to the right, so that
+    /// the right child of the left child takes this node's place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node has no left child, or the left child has no right
+    /// child.
+    pub fn rotate_left_right(&mut self) {
+        self.try_rotate_left_right()
+            .expect("rotate_left_right requires a left child with a right child")
+    }
+
+    /// Like `rotate_left_right`, but fails with `BinaryTreeError::MissingChild`
+    /// instead of panicking if a required child is missing. The tree is left
+    /// unchanged on failure.
+    pub fn try_rotate_left_right(&mut self) -> Result<(), BinaryTreeError> {
+        self.try_left()?
+            .ok_or(BinaryTreeError::MissingChild(Side::Left))?
+            .try_rotate_left()?;
+        self.try_rotate_right()
+    }
+
+    /// Rotate the right child to the right, then this node to the left, so
+    /// that the left child of the right child takes this node's place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node has no right child, or the right child has no left
+    /// child.
+    pub fn rotate_right_left(&mut self) {
+        self.try_rotate_right_left()
+            .expect("rotate_right_left requires a right child with a left child")
+    }
+
+    /// Like `rotate_right_left`, but fails with `BinaryTreeError::MissingChild`
+    /// instead of panicking if a required child is missing. The tree is left
+    /// unchanged on failure.
+    pub fn try_rotate_right_left(&mut self) -> Result<(), BinaryTreeError> {
+        self.try_right()?
+            .ok_or(BinaryTreeError::MissingChild(Side::Right))?
+            .try_rotate_right()?;
+        self.try_rotate_left()
+    }
+
+    /// Remove this node and move its only child subtree, if any, into its
+    /// place, returning the value of the removed node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node has two children. The caller must not remove the
+    /// root of a tree without children, which would leave the tree empty.
+    pub(crate) fn splice_out(mut self) -> T {
+        let child_id = match (self.left().is_some(), self.right().is_some()) {
+            (true, true) => panic!("cannot splice out a node with two children"),
+            (true, false) => self.slot_id(Side::Left),
+            // With no children, the right placeholder takes over the slot.
+            (false, _) => self.slot_id(Side::Right),
+        };
+
+        self.inner.insert_id_before(child_id);
+        self.inner.detach();
+        let value = self.inner.value().value.take().expect("exists");
+
+        // Free this node along with the empty slot it still holds.
+        let id = self.inner.id();
+        clear(self.inner.tree(), self.free, id);
+        self.free.push(id);
+        value
+    }
+
+    /// Moves the values of `subtree` into the empty slot with the given id.
+    fn graft(&mut self, slot_id: NodeId, mut subtree: BinaryTree<T>) {
+        let subtree_root_id = subtree.root_id();
+        let value = subtree.root_mut().inner.value().value.take();
+        fill(
+            self.inner.tree(),
+            self.free,
+            slot_id,
+            value.expect("exists"),
+        );
+        move_children(
+            &mut subtree.inner,
+            subtree_root_id,
+            self.inner.tree(),
+            self.free,
+            slot_id,
+        );
+    }
+
+    /// Moves the child subtree on the given side into a new tree, freeing
+    /// its nodes here.
+    fn take_child(&mut self, side: Side) -> Option<BinaryTree<T>> {
+        let slot_id = self.slot_id(side);
+        let value = self.node(slot_id).inner.value().value.take()?;
+
+        let mut subtree = BinaryTree::new(value);
+        let subtree_root_id = subtree.root_id();
+        move_children(
+            self.inner.tree(),
+            slot_id,
+            &mut subtree.inner,
+            &mut subtree.free,
+            subtree_root_id,
+        );
+        clear(self.inner.tree(), self.free, slot_id);
+        Some(subtree)
+    }
+
+    /// Removes the child subtree on the given side, freeing its nodes.
+    fn remove_child(&mut self, side: Side) -> Option<T> {
+        let slot_id = self.slot_id(side);
+        let value = self.node(slot_id).inner.value().value.take()?;
+        clear(self.inner.tree(), self.free, slot_id);
+        Some(value)
+    }
+
+    pub(crate) fn slot(&mut self, side: Side) -> Slot<'_, T> {
+        let slot_id = self.slot_id(side);
+        Slot(self.node(slot_id))
+    }
+
+    /// Return the parent, or `None` for the root.
+    pub(crate) fn parent(&mut self) -> Option<BinaryNodeMut<'_, T>> {
+        let mut parent = self.inner.parent().expect("always has parent");
+        // The parent of the root is the placeholder root of the inner tree.
+        if parent.value().is_empty() {
+            return None;
+        }
+
+        Some(BinaryNodeMut::wrap(parent, self.free))
+    }
+
+    fn wrap(mut node: NodeMut<'a, NodeData<T>>, free: &'a mut Vec<NodeId>) -> Self {
+        let generation = node.value().generation;
+        Self {
+            inner: node,
+            free,
+            generation,
+        }
+    }
+}
+
+/// Child slot of a node, which is either empty or holds a node.
+pub(crate) struct Slot<'a, T>(BinaryNodeMut<'a, T>);
+
+impl<'a, T> Slot<'a, T> {
+    pub(crate) fn is_vacant(&mut self) -> bool {
+        self.0.inner.value().is_empty()
+    }
+
+    /// Puts `value` into the slot, which must be vacant.
+    pub(crate) fn fill(mut self, value: T) -> BinaryNodeMut<'a, T> {
+        let id = self.0.inner.id();
+        fill(self.0.inner.tree(), self.0.free, id, value);
+        self.0
+    }
+
+    /// Returns the node in the slot, which must be occupied.
+    pub(crate) fn into_node(self) -> BinaryNodeMut<'a, T> {
+        self.0
+    }
+}
+
+/// Returns the child slot on the given side of a node, failing if the node
+/// doesn't have exactly two slots.
+fn slot<T>(
+    node: NodeRef<'_, NodeData<T>>,
+    side: Side,
+) -> Result<NodeRef<'_, NodeData<T>>, BinaryTreeError> {
+    let mut children = node.children();
+    match (children.next(), children.next(), children.next()) {
+        (Some(left), Some(right), None) => Ok(match side {
+            Side::Left => left,
+            Side::Right => right,
+        }),
+        _ => Err(BinaryTreeError::Corrupted(BinaryNodeId(
+            node.id(),
+            node.value().generation,
+        ))),
+    }
+}
+
+/// Returns an empty, childless node, reusing a freed one if there is any.
+fn alloc<T>(tree: &mut Tree<NodeData<T>>, free: &mut Vec<NodeId>) -> NodeId {
+    free.pop()
+        .unwrap_or_else(|| tree.orphan(NodeData::empty()).id())
+}
+
+/// Puts `value` into the node with the given id, giving it empty slots of its
+/// own if it was an empty slot.
+fn fill<T>(tree: &mut Tree<NodeData<T>>, free: &mut Vec<NodeId>, id: NodeId, value: T) {
+    let mut node = tree.get_mut(id).expect("exists");
+    node.value().value = Some(value);
+    if !node.has_children() {
+        for _ in 0..2 {
+            let slot_id = alloc(node.tree(), free);
+            node.append_id(slot_id);
+        }
+    }
+}
+
+/// Empties the node with the given id and frees all nodes below it, leaving
+/// an empty slot. Generations are bumped so ids of the removed values aren't
+/// found again once the nodes are reused.
+fn clear<T>(tree: &mut Tree<NodeData<T>>, free: &mut Vec<NodeId>, id: NodeId) {
+    let mut stack = vec![id];
+    while let Some(node_id) = stack.pop() {
+        stack.extend(
+            tree.get(node_id)
+                .expect("exists")
+                .children()
+                .map(|child| child.id()),
+        );
+
+        let mut node = tree.get_mut(node_id).expect("exists");
+        let data = node.value();
+        data.value = None;
+        data.generation += 1;
+        if node_id != id {
+            // Children were collected above and are detached in turn, so
+            // freed nodes end up childless.
+            node.detach();
+            free.push(node_id);
+        }
+    }
+}
+
+/// Moves the values below `src_id` into the empty slots below `dst_id`,
+/// preserving the shape of the subtree. Moved nodes are left holding `None`.
+fn move_children<T>(
+    src: &mut Tree<NodeData<T>>,
+    src_id: NodeId,
+    dst: &mut Tree<NodeData<T>>,
+    dst_free: &mut Vec<NodeId>,
+    dst_id: NodeId,
+) {
+    let mut stack = vec![(src_id, dst_id)];
+    while let Some((src_id, dst_id)) = stack.pop() {
+        let src_children: Vec<NodeId> = src
+            .get(src_id)
+            .expect("exists")
+            .children()
+            .map(|child| child.id())
+            .collect();
+        let dst_children: Vec<NodeId> = dst
+            .get(dst_id)
+            .expect("exists")
+            .children()
+            .map(|child| child.id())
+            .collect();
+
+        for (src_child_id, dst_child_id) in src_children.into_iter().zip(dst_children) {
+            let value = src
+                .get_mut(src_child_id)
+                .expect("exists")
+                .value()
+                .value
+                .take();
+            if let Some(value) = value {
+                fill(dst, dst_free, dst_child_id, value);
+                stack.push((src_child_id, dst_child_id));
+            }
+        }
     }
 }
 
@@ -149,4 +926,300 @@ mod tests {
         assert!(left.left().is_none());
         assert!(left.right().is_none());
     }
+
+    #[test]
+    fn parent_and_sibling_navigation_works() {
+        let tree = binary_tree! {
+            1 => {
+                left: 2 => {
+                    right: 4,
+                },
+                right: 3,
+            }
+        };
+
+        let root = tree.root();
+        assert!(root.is_root());
+        assert!(root.parent().is_none());
+        assert!(root.sibling().is_none());
+        assert!(!root.is_left_child() && !root.is_right_child());
+
+        let two = root.left().unwrap();
+        assert!(two.is_left_child() && !two.is_right_child());
+        assert_eq!(two.parent(), Some(root));
+        assert_eq!(two.sibling().map(|n| *n.value()), Some(3));
+
+        let four = two.right().unwrap();
+        assert!(four.is_right_child());
+        // The left slot of 2 only holds a placeholder.
+        assert!(four.sibling().is_none());
+
+        let ancestors: Vec<_> = four.ancestors().map(|n| *n.value()).collect();
+        assert_eq!(ancestors, vec![2, 1]);
+        assert_eq!(root.ancestors().count(), 0);
+    }
+
+    #[test]
+    fn ids_survive_mutation() {
+        let mut tree = BinaryTree::new("root");
+        let root_id = tree.root().id();
+        let left_id = tree.root_mut().set_left("left").id();
+        let right_id = tree.root_mut().set_right("right").id();
+
+        tree.get_mut(left_id).unwrap().set_left("leftleft");
+        *tree.get_mut(right_id).unwrap().value() = "RIGHT";
+
+        assert_eq!(tree.get(root_id).map(|n| *n.value()), Some("root"));
+        assert_eq!(tree.get(left_id).map(|n| *n.value()), Some("left"));
+        assert_eq!(tree.get(right_id).map(|n| *n.value()), Some("RIGHT"));
+        assert_eq!(
+            tree.get(left_id)
+                .unwrap()
+                .left()
+                .unwrap()
+                .parent()
+                .map(|n| n.id()),
+            Some(left_id)
+        );
+    }
+
+    #[test]
+    fn placeholder_ids_are_not_found() {
+        let mut tree = BinaryTree::new(0);
+        let child_id = {
+            let mut other = BinaryTree::new(0);
+            other.root_mut().set_left(1);
+            other.root().left().unwrap().id()
+        };
+
+        // The id of the left child in `other` points at a placeholder slot here.
+        assert!(tree.get(child_id).is_none());
+        assert!(tree.get_mut(child_id).is_none());
+    }
+
+    #[test]
+    fn take_and_remove_subtrees_work() {
+        let mut tree = binary_tree! {
+            1 => {
+                left: 2 => {
+                    right: 4,
+                },
+                right: 3 => {
+                    left: 5,
+                },
+            }
+        };
+        let three_id = tree.root().right().unwrap().id();
+
+        let taken = tree.root_mut().take_left().unwrap();
+        assert_eq!(taken.root().value(), &2);
+        assert!(taken.root().left().is_none());
+        assert_eq!(taken.root().right().map(|n| *n.value()), Some(4));
+
+        let mut root = tree.root_mut();
+        assert!(root.left().is_none());
+        assert!(root.take_left().is_none());
+        assert_eq!(root.remove_right(), Some(3));
+        assert!(root.right().is_none());
+        assert!(root.remove_right().is_none());
+        assert!(tree.get(three_id).is_none());
+        assert_eq!(tree.preorder().count(), 1);
+
+        // Vacated slots can be filled again.
+        tree.root_mut().set_left(6).set_right(7);
+        let values: Vec<_> = tree.preorder().map(|n| *n.value()).collect();
+        assert_eq!(values, vec![1, 6, 7]);
+    }
+
+    #[test]
+    fn removed_nodes_are_reused() {
+        let mut tree = binary_tree! {
+            1 => {
+                left: 2 => {
+                    left: 3,
+                    right: 4,
+                },
+            }
+        };
+        let two_id = tree.root().left().unwrap().id();
+
+        // The arena only grows once, for the slots of the right child.
+        let mut arena_len = None;
+        for _ in 0..100 {
+            let subtree = tree.root_mut().take_left().unwrap();
+            assert!(tree.get(two_id).is_none());
+            tree.root_mut().attach_left(subtree);
+            tree.root_mut().set_right(5);
+            assert_eq!(tree.root_mut().remove_right(), Some(5));
+            assert_eq!(tree.arena_len(), *arena_len.get_or_insert(tree.arena_len()));
+        }
+
+        // The slot of 2 was reused, but its old id still isn't found.
+        assert!(tree.get(two_id).is_none());
+        let two = tree.root().left().unwrap();
+        assert_eq!(two.value(), &2);
+        assert_ne!(two.id(), two_id);
+        assert_eq!(
+            format!("{:?}", tree),
+            "1 => { left: 2 => { left: 3, right: 4 } }"
+        );
+    }
+
+    #[test]
+    fn attach_subtrees_works() {
+        let mut tree = binary_tree! {
+            "root" => {
+                right: "old right",
+            }
+        };
+        let left = binary_tree! {
+            "a" => {
+                right: "b" => {
+                    left: "c",
+                },
+            }
+        };
+
+        let mut root = tree.root_mut();
+        assert!(root.attach_left(left).is_none());
+        let previous = root.attach_right(binary_tree!("new right")).unwrap();
+        assert_eq!(previous.root().value(), &"old right");
+
+        let values: Vec<_> = tree.preorder().map(|n| *n.value()).collect();
+        assert_eq!(values, vec!["root", "a", "b", "c", "new right"]);
+
+        let a = tree.root().left().unwrap();
+        assert!(a.left().is_none());
+        let b = a.right().unwrap();
+        assert_eq!(b.left().map(|n| *n.value()), Some("c"));
+        assert!(b.right().is_none());
+        assert_eq!(tree.get(b.id()).map(|n| *n.value()), Some("b"));
+    }
+
+    #[test]
+    fn splice_out_promotes_only_child() {
+        let mut tree = binary_tree! {
+            1 => {
+                left: 2 => {
+                    right: 3 => {
+                        left: 4,
+                    },
+                },
+            }
+        };
+
+        let two_id = tree.root().left().unwrap().id();
+        assert_eq!(tree.get_mut(two_id).unwrap().splice_out(), 2);
+        assert!(tree.get(two_id).is_none());
+        let three = tree.root().left().unwrap();
+        assert_eq!(three.value(), &3);
+        assert_eq!(three.parent().map(|n| *n.value()), Some(1));
+
+        // Splicing out the root promotes its child to be the new root.
+        tree.root_mut().splice_out();
+        assert_eq!(tree.root().value(), &3);
+        assert!(tree.root().is_root());
+
+        let four_id = tree.root().left().unwrap().id();
+        assert_eq!(tree.get_mut(four_id).unwrap().splice_out(), 4);
+        assert!(tree.root().is_leaf());
+
+        // Spliced out nodes are reused, without their ids being found again.
+        let arena_len = tree.arena_len();
+        let five_id = tree.root_mut().set_left(5).id();
+        assert_eq!(tree.arena_len(), arena_len);
+        assert!(tree.get(two_id).is_none() && tree.get(four_id).is_none());
+        assert_eq!(tree.get(five_id).map(|n| *n.value()), Some(5));
+    }
+
+    #[test]
+    fn rotations_work() {
+        let mut tree = binary_tree! {
+            'p' => {
+                left: 'a',
+                right: 'q' => {
+                    left: 'b',
+                    right: 'c',
+                },
+            }
+        };
+        let p_id = tree.root().id();
+
+        let mut root = tree.root_mut();
+        root.rotate_left();
+        assert_eq!(root.value(), &'p');
+        let values: Vec<_> = tree.preorder().map(|n| *n.value()).collect();
+        assert_eq!(values, vec!['q', 'p', 'a', 'b', 'c']);
+        assert_eq!(tree.root().left().map(|n| n.id()), Some(p_id));
+
+        tree.root_mut().rotate_right();
+        let values: Vec<_> = tree.preorder().map(|n| *n.value()).collect();
+        assert_eq!(values, vec!['p', 'a', 'q', 'b', 'c']);
+        assert_eq!(tree.root().id(), p_id);
+        assert!(tree.root().right().unwrap().left().unwrap().is_left_child());
+    }
+
+    #[test]
+    fn double_rotations_work() {
+        let mut tree = binary_tree! {
+            'c' => {
+                left: 'a' => {
+                    right: 'b',
+                },
+            }
+        };
+        let values =
+            |tree: &BinaryTree<char>| -> String { tree.preorder().map(|n| *n.value()).collect() };
+
+        tree.root_mut().rotate_left_right();
+        assert_eq!(values(&tree), "bac");
+        assert!(tree.is_perfect());
+
+        let mut tree = binary_tree! {
+            'x' => {
+                right: 'a' => {
+                    right: 'c' => {
+                        left: 'b',
+                    },
+                },
+            }
+        };
+        let a_id = tree.root().right().unwrap().id();
+        tree.get_mut(a_id).unwrap().rotate_right_left();
+        assert_eq!(values(&tree), "xbac");
+        let b = tree.root().right().unwrap();
+        assert_eq!(b.left().map(|n| n.id()), Some(a_id));
+        assert!(b.is_perfect());
+    }
+
+    #[test]
+    fn try_api_reports_errors() {
+        let mut tree = binary_tree! {
+            1 => {
+                left: 2,
+            }
+        };
+        let left_id = tree.root().left().unwrap().id();
+
+        let mut root = tree.root_mut();
+        assert_eq!(
+            root.try_set_left(3).err(),
+            Some((BinaryTreeError::Occupied(Side::Left), 3))
+        );
+        assert_eq!(root.try_set_right(3).map(|mut n| *n.value()), Ok(3));
+        assert_eq!(
+            root.try_rotate_left_right(),
+            Err(BinaryTreeError::MissingChild(Side::Right))
+        );
+        assert_eq!(format!("{:?}", tree), "1 => { left: 2, right: 3 }");
+
+        let removed = tree.root_mut().take_left().unwrap();
+        assert_eq!(
+            tree.try_get(left_id).err(),
+            Some(BinaryTreeError::NotFound(left_id))
+        );
+        assert!(tree.try_get_mut(left_id).is_err());
+        assert_eq!(tree.try_get(tree.root().id()).unwrap().try_left(), Ok(None));
+        assert_eq!(*removed.root().value(), 2);
+    }
 }
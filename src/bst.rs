@@ -0,0 +1,474 @@
+//! Binary search tree map built on `BinaryTree`.
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+
+use crate::binary_tree::{BinaryNodeId, BinaryNodeRef, BinaryTree};
+use crate::iter::Inorder;
+
+/// Ordered map backed by an (unbalanced) binary search tree.
+///
+/// Every key in the left subtree of a node is less than the key of the node,
+/// and every key in the right subtree is greater.
+pub struct BinarySearchTree<K, V> {
+    tree: Option<BinaryTree<(K, V)>>,
+    len: usize,
+}
+
+/// Entry stored in a search tree, ordered by its key.
+pub(crate) trait Keyed {
+    type Key;
+    type Value;
+
+    fn key(&self) -> &Self::Key;
+
+    fn value(&self) -> &Self::Value;
+}
+
+impl<K, V> Keyed for (K, V) {
+    type Key = K;
+    type Value = V;
+
+    fn key(&self) -> &K {
+        &self.0
+    }
+
+    fn value(&self) -> &V {
+        &self.1
+    }
+}
+
+/// Result of searching for a key from the root of a non-empty tree.
+pub(crate) enum Search {
+    Found(BinaryNodeId),
+    /// The key is not present and belongs below the given node, on the left
+    /// for `Ordering::Less` and on the right for `Ordering::Greater`.
+    Vacant(BinaryNodeId, Ordering),
+}
+
+pub(crate) fn search<T, Q>(tree: &BinaryTree<T>, key: &Q) -> Search
+where
+    T: Keyed,
+    T::Key: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    let mut node = tree.root();
+    loop {
+        let ordering = key.cmp(node.value().key().borrow());
+        let next = match ordering {
+            Ordering::Equal => return Search::Found(node.id()),
+            Ordering::Less => node.left(),
+            Ordering::Greater => node.right(),
+        };
+        match next {
+            Some(next) => node = next,
+            None => return Search::Vacant(node.id(), ordering),
+        }
+    }
+}
+
+impl<K: Ord, V> BinarySearchTree<K, V> {
+    /// Creates an empty tree.
+    pub fn new() -> Self {
+        Self { tree: None, len: 0 }
+    }
+
+    /// Returns the number of entries in the tree.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the tree contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the underlying binary tree, or `None` if the tree is empty.
+    pub fn tree(&self) -> Option<&BinaryTree<(K, V)>> {
+        self.tree.as_ref()
+    }
+
+    /// Inserts a key-value pair, returning the previous value for the key.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let tree = match &mut self.tree {
+            Some(tree) => tree,
+            None => {
+                self.tree = Some(BinaryTree::new((key, value)));
+                self.len = 1;
+                return None;
+            }
+        };
+
+        match search(tree, &key) {
+            Search::Found(id) => {
+                let mut node = tree.get_mut(id).expect("exists");
+                Some(std::mem::replace(&mut node.value().1, value))
+            }
+            Search::Vacant(parent_id, ordering) => {
+                let mut parent = tree.get_mut(parent_id).expect("exists");
+                if ordering == Ordering::Less {
+                    parent.set_left((key, value));
+                } else {
+                    parent.set_right((key, value));
+                }
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    /// Returns a reference to the value for the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).map(|(_key, value)| value)
+    }
+
+    /// Returns references to the stored key and the value for the key.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let tree = self.tree.as_ref()?;
+        match search(tree, key) {
+            Search::Found(id) => tree.get(id).map(entry),
+            Search::Vacant(..) => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value for the key.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let tree = self.tree.as_mut()?;
+        match search(tree, key) {
+            Search::Found(id) => tree.get_mut(id).map(|node| &mut node.into_value().1),
+            Search::Vacant(..) => None,
+        }
+    }
+
+    /// Returns true if the tree contains the key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Removes the key, returning its value if it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_key, value)| value)
+    }
+
+    /// Removes the key, returning the stored key and value if it was present.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let tree = self.tree.as_mut()?;
+        let id = match search(tree, key) {
+            Search::Found(id) => id,
+            Search::Vacant(..) => return None,
+        };
+
+        self.len -= 1;
+        if self.len == 0 {
+            let tree = self.tree.take().expect("exists");
+            return Some(tree.into_root_value());
+        }
+
+        let node = tree.get(id).expect("exists");
+        let successor_id = match (node.left(), node.right()) {
+            (Some(_), Some(right)) => leftmost(right).id(),
+            _ => return Some(tree.get_mut(id).expect("exists").splice_out()),
+        };
+
+        // With two children, the in-order successor (which has no left child)
+        // is spliced out and its entry moved into the removed node.
+        let successor = tree.get_mut(successor_id).expect("exists").splice_out();
+        let mut node = tree.get_mut(id).expect("exists");
+        Some(std::mem::replace(node.value(), successor))
+    }
+
+    /// Returns the entry with the smallest key.
+    pub fn min(&self) -> Option<(&K, &V)> {
+        self.tree.as_ref().map(|tree| entry(leftmost(tree.root())))
+    }
+
+    /// Returns the entry with the largest key.
+    pub fn max(&self) -> Option<(&K, &V)> {
+        self.tree.as_ref().map(|tree| entry(rightmost(tree.root())))
+    }
+
+    /// Returns the entry with the largest key less than or equal to `key`.
+    pub fn floor<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        floor(self.tree.as_ref()?, key).map(entry)
+    }
+
+    /// Returns the entry with the smallest key greater than or equal to `key`.
+    pub fn ceiling<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        ceiling(self.tree.as_ref()?, key).map(entry)
+    }
+
+    /// Returns an iterator over the entries in ascending key order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.tree.as_ref().map(BinaryTree::inorder),
+        }
+    }
+}
+
+impl<K: Ord, V> Default for BinarySearchTree<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, K: Ord, V> IntoIterator for &'a BinarySearchTree<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub(crate) fn entry<T: Keyed>(node: BinaryNodeRef<'_, T>) -> (&T::Key, &T::Value) {
+    let node = node.value();
+    (node.key(), node.value())
+}
+
+/// Checks that the keys of a search tree are in ascending order and that it
+/// holds `len` entries, describing the first violation found.
+pub(crate) fn validate_order<T>(tree: Option<&BinaryTree<T>>, len: usize) -> Result<(), String>
+where
+    T: Keyed,
+    T::Key: Ord,
+{
+    let tree = match tree {
+        Some(tree) => tree,
+        None if len == 0 => return Ok(()),
+        None => return Err(format!("empty tree with length {}", len)),
+    };
+
+    let mut count = 0;
+    let mut previous: Option<&T::Key> = None;
+    for node in tree.inorder() {
+        let key = node.value().key();
+        if previous.is_some_and(|previous| previous >= key) {
+            return Err(format!(
+                "key at in-order position {} is out of order",
+                count
+            ));
+        }
+        previous = Some(key);
+        count += 1;
+    }
+    if count != len {
+        return Err(format!("tree has {} nodes but length {}", count, len));
+    }
+    Ok(())
+}
+
+/// Returns the node with the largest key less than or equal to `key`.
+pub(crate) fn floor<'a, T, Q>(tree: &'a BinaryTree<T>, key: &Q) -> Option<BinaryNodeRef<'a, T>>
+where
+    T: Keyed,
+    T::Key: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    let mut node = Some(tree.root());
+    let mut floor = None;
+    while let Some(current) = node {
+        match key.cmp(current.value().key().borrow()) {
+            Ordering::Equal => return Some(current),
+            Ordering::Less => node = current.left(),
+            Ordering::Greater => {
+                floor = Some(current);
+                node = current.right();
+            }
+        }
+    }
+    floor
+}
+
+/// Returns the node with the smallest key greater than or equal to `key`.
+pub(crate) fn ceiling<'a, T, Q>(tree: &'a BinaryTree<T>, key: &Q) -> Option<BinaryNodeRef<'a, T>>
+where
+    T: Keyed,
+    T::Key: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    let mut node = Some(tree.root());
+    let mut ceiling = None;
+    while let Some(current) = node {
+        match key.cmp(current.value().key().borrow()) {
+            Ordering::Equal => return Some(current),
+            Ordering::Less => {
+                ceiling = Some(current);
+                node = current.left();
+            }
+            Ordering::Greater => node = current.right(),
+        }
+    }
+    ceiling
+}
+
+pub(crate) fn leftmost<T>(mut node: BinaryNodeRef<'_, T>) -> BinaryNodeRef<'_, T> {
+    while let Some(left) = node.left() {
+        node = left;
+    }
+    node
+}
+
+pub(crate) fn rightmost<T>(mut node: BinaryNodeRef<'_, T>) -> BinaryNodeRef<'_, T> {
+    while let Some(right) = node.right() {
+        node = right;
+    }
+    node
+}
+
+/// Iterator over the entries of a `BinarySearchTree` in ascending key order.
+pub struct Iter<'a, K, V> {
+    inner: Option<Inorder<'a, (K, V)>>,
+}
+
+impl<'a, K, V> Clone for Iter<'a, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.as_mut()?.next().map(entry)
+    }
+}
+
+/// Fills `map` with the keys `0..16`, then repeatedly inserts and removes
+/// keys, both at the bottom and from the middle of the tree, checking that
+/// the arena doesn't grow past the map's largest size.
+#[cfg(test)]
+pub(crate) fn check_churn<M>(
+    map: &mut M,
+    insert: fn(&mut M, i32),
+    remove: fn(&mut M, i32),
+    arena_len: fn(&M) -> usize,
+) {
+    for key in 0..16 {
+        insert(map, key);
+    }
+    let before = arena_len(map);
+
+    for i in 0..1_000 {
+        insert(map, 100 + i % 100);
+        remove(map, 100 + i % 100);
+        remove(map, i % 16);
+        insert(map, i % 16);
+    }
+    // At most one more entry was ever in the map.
+    assert!(arena_len(map) <= before + 2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(tree: &BinarySearchTree<i32, i32>) -> Vec<i32> {
+        tree.iter().map(|(key, _value)| *key).collect()
+    }
+
+    #[test]
+    fn insert_get_and_iterate_work() {
+        let mut tree = BinarySearchTree::new();
+        for key in &[5, 3, 8, 1, 4, 7, 9] {
+            assert_eq!(tree.insert(*key, key * 10), None);
+        }
+        assert_eq!(tree.insert(4, 44), Some(40));
+
+        assert_eq!(tree.len(), 7);
+        assert_eq!(keys(&tree), vec![1, 3, 4, 5, 7, 8, 9]);
+        assert_eq!(tree.get(&4), Some(&44));
+        assert_eq!(tree.get(&6), None);
+        assert!(tree.contains_key(&9));
+
+        *tree.get_mut(&9).unwrap() += 1;
+        assert_eq!(tree.get(&9), Some(&91));
+
+        assert_eq!(tree.min(), Some((&1, &10)));
+        assert_eq!(tree.max(), Some((&9, &91)));
+        assert_eq!(tree.floor(&6), Some((&5, &50)));
+        assert_eq!(tree.floor(&7), Some((&7, &70)));
+        assert_eq!(tree.floor(&0), None);
+        assert_eq!(tree.ceiling(&6), Some((&7, &70)));
+        assert_eq!(tree.ceiling(&10), None);
+    }
+
+    #[test]
+    fn remove_works() {
+        let mut tree = BinarySearchTree::new();
+        for key in &[5, 3, 8, 1, 4, 7, 9, 6] {
+            tree.insert(*key, *key);
+        }
+
+        // Node with two children.
+        assert_eq!(tree.remove(&5), Some(5));
+        assert_eq!(keys(&tree), vec![1, 3, 4, 6, 7, 8, 9]);
+        // Node with one child.
+        assert_eq!(tree.remove(&7), Some(7));
+        // Leaf.
+        assert_eq!(tree.remove(&1), Some(1));
+        assert_eq!(tree.remove(&1), None);
+        assert_eq!(keys(&tree), vec![3, 4, 6, 8, 9]);
+        assert_eq!(tree.len(), 5);
+
+        for key in &[3, 4, 6, 8, 9] {
+            assert_eq!(tree.remove(key), Some(*key));
+        }
+        assert!(tree.is_empty());
+        assert!(tree.tree().is_none());
+        assert_eq!(tree.min(), None);
+
+        tree.insert(2, 2);
+        assert_eq!(keys(&tree), vec![2]);
+    }
+
+    #[test]
+    fn churn_does_not_grow_arena() {
+        let mut tree = BinarySearchTree::new();
+        check_churn(
+            &mut tree,
+            |tree, key| {
+                tree.insert(key, key);
+            },
+            |tree, key| {
+                tree.remove(&key);
+            },
+            |tree| tree.tree().unwrap().arena_len(),
+        );
+        assert_eq!(tree.len(), 16);
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::binary_tree::{BinaryNodeId, BinaryNodeRef, BinaryTree};
+use crate::zip::first_mismatch;
+
+/// Trees are equal if they have the same shape and equal values in the same
+/// places, regardless of how they were built.
+impl<T: PartialEq> PartialEq for BinaryTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        // As long as every pair of nodes has the same children, both
+        // pre-orders stay in step and end together.
+        self.preorder()
+            .zip(other.preorder())
+            .all(|(node, other)| node.value() == other.value() && shape(node) == shape(other))
+    }
+}
+
+impl<T: Eq> Eq for BinaryTree<T> {}
+
+/// Hashes the values in pre-order along with the shape, consistent with
+/// `PartialEq`.
+impl<T: Hash> Hash for BinaryTree<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for node in self.preorder() {
+            node.value().hash(state);
+            shape(node).hash(state);
+        }
+    }
+}
+
+impl<T> BinaryTree<T> {
+    /// Returns true if both trees have the same shape, regardless of values.
+    pub fn same_shape<U>(&self, other: &BinaryTree<U>) -> bool {
+        self.root().same_shape(other.root())
+    }
+
+    /// Returns true if `other` is this tree with the children of every node
+    /// swapped.
+    pub fn is_mirror_of(&self, other: &BinaryTree<T>) -> bool
+    where
+        T: PartialEq,
+    {
+        self.root().is_mirror_of(other.root())
+    }
+
+    /// Returns true if the tree is its own mirror image.
+    pub fn is_symmetric(&self) -> bool
+    where
+        T: PartialEq,
+    {
+        self.root().is_symmetric()
+    }
+
+    /// Returns true if the trees are equal up to swapping the children of any
+    /// of their nodes, comparing values with `eq`. See
+    /// `BinaryNodeRef::is_isomorphic`.
+    pub fn is_isomorphic<U, F>(&self, other: &BinaryTree<U>, eq: F) -> bool
+    where
+        F: FnMut(&T, &U) -> bool,
+    {
+        self.root().is_isomorphic(other.root(), eq)
+    }
+}
+
+impl<'a, T> BinaryNodeRef<'a, T> {
+    /// Returns true if both subtrees have the same shape, regardless of
+    /// values.
+    pub fn same_shape<U>(&self, other: BinaryNodeRef<'_, U>) -> bool {
+        first_mismatch(*self, other).is_none()
+    }
+
+    /// Returns true if `other` is this subtree with the children of every
+    /// node swapped.
+    pub fn is_mirror_of(&self, other: BinaryNodeRef<'_, T>) -> bool
+    where
+        T: PartialEq,
+    {
+        let mut stack = vec![(*self, other)];
+        while let Some((node, other)) = stack.pop() {
+            let (left, right) = shape(node);
+            if node.value() != other.value() || shape(other) != (right, left) {
+                return false;
+            }
+            stack.extend(node.left().zip(other.right()));
+            stack.extend(node.right().zip(other.left()));
+        }
+        true
+    }
+
+    /// Returns true if this subtree is its own mirror image.
+    pub fn is_symmetric(&self) -> bool
+    where
+        T: PartialEq,
+    {
+        match (self.left(), self.right()) {
+            (Some(left), Some(right)) => left.is_mirror_of(right),
+            (left, right) => left.is_none() && right.is_none(),
+        }
+    }
+
+    /// Returns true if the subtrees are equal up to swapping the children of
+    /// any of their nodes, comparing values with `eq`.
+    ///
+    /// Children are only matched up if their subtrees have the same size, so
+    /// this takes O(n²) time in the worst case.
+    pub fn is_isomorphic<U, F>(&self, other: BinaryNodeRef<'_, U>, mut eq: F) -> bool
+    where
+        F: FnMut(&T, &U) -> bool,
+    {
+        // Stage of a comparison of two nodes, named after the comparison of
+        // children whose outcome it resumes with.
+        enum Stage {
+            Start,
+            StraightLeft,
+            StraightRight,
+            SwappedLeft,
+            SwappedRight,
+        }
+
+        let sizes = subtree_sizes(*self);
+        let other_sizes = subtree_sizes(other);
+
+        // The comparisons are driven by an explicit stack so deep trees don't
+        // overflow the call stack. `result` holds the outcome of the last
+        // finished comparison.
+        let mut stack = vec![(*self, other, Stage::Start)];
+        let mut result = true;
+        while let Some((node, other, stage)) = stack.pop() {
+            let (children, next) = match (stage, result) {
+                (Stage::Start, _) => {
+                    if sizes[&node.id()] != other_sizes[&other.id()]
+                        || !eq(node.value(), other.value())
+                    {
+                        result = false;
+                        continue;
+                    }
+                    ((node.left(), other.left()), Stage::StraightLeft)
+                }
+                (Stage::StraightLeft, true) => {
+                    ((node.right(), other.right()), Stage::StraightRight)
+                }
+                (Stage::StraightLeft, false) | (Stage::StraightRight, false) => {
+                    ((node.left(), other.right()), Stage::SwappedLeft)
+                }
+                (Stage::SwappedLeft, true) => ((node.right(), other.left()), Stage::SwappedRight),
+                // Either pairing of the children matched, or neither did.
+                (Stage::StraightRight, true)
+                | (Stage::SwappedLeft, false)
+                | (Stage::SwappedRight, _) => continue,
+            };
+
+            stack.push((node, other, next));
+            match children {
+                (Some(child), Some(other_child)) => {
+                    stack.push((child, other_child, Stage::Start));
+                }
+                (child, other_child) => result = child.is_none() && other_child.is_none(),
+            }
+        }
+        result
+    }
+}
+
+/// Which children a node has.
+fn shape<T>(node: BinaryNodeRef<'_, T>) -> (bool, bool) {
+    (node.left().is_some(), node.right().is_some())
+}
+
+/// Returns the number of nodes in the subtree of every node below `root`.
+fn subtree_sizes<T>(root: BinaryNodeRef<'_, T>) -> HashMap<BinaryNodeId, usize> {
+    let mut sizes = HashMap::new();
+    for node in root.postorder() {
+        let children = [node.left(), node.right()];
+        let size = 1 + children
+            .iter()
+            .flatten()
+            .map(|child| sizes[&child.id()])
+            .sum::<usize>();
+        sizes.insert(node.id(), size);
+    }
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::BinaryTree;
+
+    #[test]
+    fn equality_is_structural() {
+        let tree = binary_tree! {
+            2 => {
+                left: 1,
+                right: 3,
+            }
+        };
+
+        // Same values and shape, but a different layout of the inner tree.
+        let mut rotated = binary_tree! {
+            1 => {
+                right: 2 => {
+                    right: 3,
+                },
+            }
+        };
+        rotated.root_mut().rotate_left();
+        assert_eq!(rotated, tree);
+
+        let mut clone = tree.clone();
+        assert_eq!(clone, tree);
+        clone.root_mut().left().unwrap().set_left(0);
+        assert_ne!(clone, tree);
+        clone.root_mut().left().unwrap().remove_left();
+        assert_eq!(clone, tree);
+
+        // Same values in pre-order, different shape.
+        assert_ne!(
+            binary_tree!(1 => { left: 2 }),
+            binary_tree!(1 => { right: 2 })
+        );
+        assert_eq!(BinaryTree::<i32>::default(), BinaryTree::new(0));
+    }
+
+    #[test]
+    fn shape_and_mirror_checks_work() {
+        let tree = binary_tree! {
+            1 => {
+                left: 2 => {
+                    left: 3,
+                    right: 4,
+                },
+                right: 2 => {
+                    left: 4,
+                    right: 3,
+                },
+            }
+        };
+        assert!(tree.is_symmetric());
+        assert!(tree.is_mirror_of(&tree));
+        assert!(tree.same_shape(&tree.map_ref(|value| value.to_string())));
+
+        let lopsided = binary_tree! {
+            1 => {
+                left: 2 => {
+                    right: 3,
+                },
+                right: 2 => {
+                    right: 3,
+                },
+            }
+        };
+        assert!(!lopsided.is_symmetric());
+        assert!(!lopsided.same_shape(&tree));
+
+        let left = lopsided.root().left().unwrap();
+        let mirrored = binary_tree!(2 => { left: 3 });
+        assert!(left.is_mirror_of(mirrored.root()));
+        assert!(!left.is_mirror_of(left));
+    }
+
+    #[test]
+    fn isomorphism_allows_swapping_children() {
+        // (a + b) * c and c * (b + a)
+        let tree = binary_tree! {
+            "*" => {
+                left: "+" => {
+                    left: "a",
+                    right: "b",
+                },
+                right: "c",
+            }
+        };
+        let swapped = binary_tree! {
+            "*" => {
+                left: "c",
+                right: "+" => {
+                    left: "b",
+                    right: "a",
+                },
+            }
+        };
+        assert!(tree.is_isomorphic(&swapped, |a, b| a == b));
+        assert!(
+            !tree.is_isomorphic(&swapped.map_ref(|value| value.replace('a', "d")), |a, b| a
+                == b)
+        );
+        assert!(!tree.is_isomorphic(&binary_tree!("*" => { left: "c" }), |a, b| a == b));
+
+        // Only the swapped pairing of the root's children matches.
+        let tree = binary_tree!(1 => { left: 2, right: 2 => { left: 3 } });
+        let other = binary_tree!(1 => { left: 2 => { right: 3 }, right: 2 });
+        assert!(tree.is_isomorphic(&other, |a, b| a == b));
+        assert!(!tree.is_isomorphic(&other, |_, b| *b != 3));
+    }
+
+    #[test]
+    fn hash_is_consistent_with_equality() {
+        let mut set = HashSet::new();
+        set.insert(binary_tree!(1 => { left: 2 }));
+        set.insert(binary_tree!(1 => { right: 2 }));
+        set.insert(binary_tree!(1 => { left: 2 }).clone());
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&binary_tree!(1 => { right: 2 })));
+    }
+}
@@ -0,0 +1,207 @@
+use std::fmt::{self, Debug, Display, Formatter};
+
+use crate::binary_tree::{BinaryNodeRef, BinaryTree};
+
+/// Formats the tree in the syntax accepted by the `binary_tree!` macro, e.g.
+/// `1 => { left: 2, right: 3 => { right: 4 } }`. The alternate form (`{:#?}`)
+/// puts every child on its own line.
+impl<T: Debug> Debug for BinaryTree<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        enum Step<'a, T> {
+            Node(BinaryNodeRef<'a, T>, usize),
+            Child(&'static str, BinaryNodeRef<'a, T>, usize),
+            Close(usize),
+        }
+
+        let pretty = f.alternate();
+        let indent = |f: &mut Formatter<'_>, depth: usize| -> fmt::Result {
+            write!(f, "\n{:width$}", "", width = depth * 4)
+        };
+
+        // Formatting is driven by an explicit stack so deep trees don't
+        // overflow the call stack.
+        let mut stack = vec![Step::Node(self.root(), 0)];
+        while let Some(step) = stack.pop() {
+            let (node, depth) = match step {
+                Step::Node(node, depth) => (node, depth),
+                Step::Child(side, node, depth) => {
+                    if pretty {
+                        indent(f, depth)?;
+                    } else if side == "right" && node.sibling().is_some() {
+                        f.write_str(", ")?;
+                    } else {
+                        f.write_str(" ")?;
+                    }
+                    write!(f, "{}: ", side)?;
+                    (node, depth)
+                }
+                Step::Close(depth) => {
+                    if pretty {
+                        indent(f, depth)?;
+                    } else {
+                        f.write_str(" ")?;
+                    }
+                    f.write_str("}")?;
+                    if pretty && depth > 0 {
+                        f.write_str(",")?;
+                    }
+                    continue;
+                }
+            };
+
+            write!(f, "{:?}", node.value())?;
+            if node.left().is_none() && node.right().is_none() {
+                if pretty && depth > 0 {
+                    f.write_str(",")?;
+                }
+                continue;
+            }
+
+            f.write_str(" => {")?;
+            stack.push(Step::Close(depth));
+            stack.extend(
+                node.right()
+                    .map(|right| Step::Child("right", right, depth + 1)),
+            );
+            stack.extend(node.left().map(|left| Step::Child("left", left, depth + 1)));
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders the tree top-down with box-drawing characters, see `pretty`.
+impl<T: Display> Display for BinaryTree<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.pretty(), f)
+    }
+}
+
+impl<T> BinaryTree<T> {
+    /// Returns a renderer drawing the tree top-down with box-drawing
+    /// characters, see `BinaryNodeRef::pretty`.
+    pub fn pretty(&self) -> Pretty<'_, T> {
+        self.root().pretty()
+    }
+}
+
+impl<'a, T> BinaryNodeRef<'a, T> {
+    /// Returns a renderer drawing this subtree top-down with box-drawing
+    /// characters. Nodes with children always list the left child first and
+    /// the right child second, with `∅` marking an absent child:
+    ///
+    /// ```text
+    /// 1
+    /// ├── 2
+    /// └── 3
+    ///     ├── ∅
+    ///     └── 4
+    /// ```
+    pub fn pretty(&self) -> Pretty<'a, T> {
+        Pretty { root: *self }
+    }
+}
+
+/// Top-down renderer of a subtree, created by `BinaryNodeRef::pretty`.
+pub struct Pretty<'a, T> {
+    root: BinaryNodeRef<'a, T>,
+}
+
+impl<'a, T: Display> Display for Pretty<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Each entry is a child slot with the prefix drawn before it and
+        // whether it is the last (right) slot of its parent.
+        let mut stack: Vec<(Option<BinaryNodeRef<'a, T>>, String, bool)> = Vec::new();
+
+        write!(f, "{}", self.root.value())?;
+        push_children(&mut stack, self.root, String::new());
+        while let Some((node, prefix, is_last)) = stack.pop() {
+            let connector = if is_last { "└── " } else { "├── " };
+            write!(f, "\n{}{}", prefix, connector)?;
+
+            let node = match node {
+                Some(node) => node,
+                None => {
+                    f.write_str("∅")?;
+                    continue;
+                }
+            };
+            write!(f, "{}", node.value())?;
+
+            let child_prefix = prefix + if is_last { "    " } else { "│   " };
+            push_children(&mut stack, node, child_prefix);
+        }
+        Ok(())
+    }
+}
+
+fn push_children<'a, T>(
+    stack: &mut Vec<(Option<BinaryNodeRef<'a, T>>, String, bool)>,
+    node: BinaryNodeRef<'a, T>,
+    prefix: String,
+) {
+    if node.left().is_none() && node.right().is_none() {
+        return;
+    }
+    stack.push((node.right(), prefix.clone(), true));
+    stack.push((node.left(), prefix, false));
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn debug_uses_macro_syntax() {
+        let tree = binary_tree! {
+            "root" => {
+                left: "a",
+                right: "b" => {
+                    right: "c",
+                },
+            }
+        };
+
+        assert_eq!(
+            format!("{:?}", tree),
+            r#""root" => { left: "a", right: "b" => { right: "c" } }"#
+        );
+        assert_eq!(
+            format!("{:#?}", tree),
+            r#""root" => {
+    left: "a",
+    right: "b" => {
+        right: "c",
+    },
+}"#
+        );
+        assert_eq!(format!("{:?}", binary_tree!(1)), "1");
+        assert_eq!(format!("{:#?}", binary_tree!(1)), "1");
+    }
+
+    #[test]
+    fn display_draws_tree() {
+        let tree = binary_tree! {
+            1 => {
+                left: 2 => {
+                    left: 4,
+                },
+                right: 3 => {
+                    right: 5,
+                },
+            }
+        };
+
+        let expected = "\
+1
+├── 2
+│   ├── 4
+│   └── ∅
+└── 3
+    ├── ∅
+    └── 5";
+        assert_eq!(tree.to_string(), expected);
+        assert_eq!(
+            tree.root().right().unwrap().pretty().to_string(),
+            "3\n├── ∅\n└── 5"
+        );
+    }
+}
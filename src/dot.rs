@@ -0,0 +1,156 @@
+//! Graphviz DOT export of a `BinaryTree`.
+
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+use crate::binary_tree::{BinaryNodeId, BinaryNodeRef, BinaryTree};
+
+impl<T> BinaryTree<T> {
+    /// Renders the tree as a Graphviz DOT graph, labelling nodes with `label`.
+    /// See `dot::Writer` for more options.
+    pub fn to_dot<F: Fn(&T) -> String>(&self, label: F) -> String {
+        Writer::new(self, label).to_string()
+    }
+}
+
+/// Writes a `BinaryTree` as a Graphviz DOT graph through its `Display` impl.
+///
+/// Edges are emitted left before right and the graph uses `ordering=out`, so
+/// Graphviz keeps left children on the left. By default a node with a single
+/// child also gets an invisible placeholder in the empty slot, so a lone
+/// right child is drawn to the right of its parent.
+pub struct Writer<'a, T> {
+    root: BinaryNodeRef<'a, T>,
+    label: Box<dyn Fn(&T) -> String + 'a>,
+    edge_labels: bool,
+    placeholders: bool,
+}
+
+impl<'a, T> Writer<'a, T> {
+    /// Creates a writer for `tree`, labelling nodes with `label`.
+    pub fn new<F: Fn(&T) -> String + 'a>(tree: &'a BinaryTree<T>, label: F) -> Self {
+        Self {
+            root: tree.root(),
+            label: Box::new(label),
+            edge_labels: false,
+            placeholders: true,
+        }
+    }
+
+    /// Sets whether edges are labelled with `L` / `R`. Off by default.
+    pub fn edge_labels(mut self, edge_labels: bool) -> Self {
+        self.edge_labels = edge_labels;
+        self
+    }
+
+    /// Sets whether invisible placeholders are drawn in the empty slot of
+    /// nodes with a single child. On by default.
+    pub fn placeholders(mut self, placeholders: bool) -> Self {
+        self.placeholders = placeholders;
+        self
+    }
+}
+
+impl<'a, T> Display for Writer<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let names: HashMap<BinaryNodeId, usize> = self
+            .root
+            .preorder()
+            .enumerate()
+            .map(|(index, node)| (node.id(), index))
+            .collect();
+
+        writeln!(f, "digraph {{")?;
+        writeln!(f, "    ordering=out;")?;
+        for node in self.root.preorder() {
+            let name = names[&node.id()];
+            writeln!(
+                f,
+                "    n{} [label=\"{}\"];",
+                name,
+                escape(&(self.label)(node.value()))
+            )?;
+
+            let has_single_child = node.left().is_some() != node.right().is_some();
+            let slots = [("L", node.left()), ("R", node.right())];
+            for (side, child) in slots.iter() {
+                let edge_label = if self.edge_labels {
+                    format!(" [label=\"{}\"]", side)
+                } else {
+                    String::new()
+                };
+                match child {
+                    Some(child) => {
+                        writeln!(f, "    n{} -> n{}{};", name, names[&child.id()], edge_label)?
+                    }
+                    None if self.placeholders && has_single_child => {
+                        writeln!(f, "    n{}{} [style=invis];", name, side)?;
+                        writeln!(f, "    n{} -> n{}{} [style=invis];", name, name, side)?;
+                    }
+                    None => {}
+                }
+            }
+        }
+        write!(f, "}}")
+    }
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_dot_works() {
+        let tree = binary_tree! {
+            "+" => {
+                left: "x",
+                right: "\"y\"",
+            }
+        };
+
+        assert_eq!(
+            tree.to_dot(|value| value.to_string()),
+            r#"digraph {
+    ordering=out;
+    n0 [label="+"];
+    n0 -> n1;
+    n0 -> n2;
+    n1 [label="x"];
+    n2 [label="\"y\""];
+}"#
+        );
+    }
+
+    #[test]
+    fn placeholders_and_edge_labels_work() {
+        let tree = binary_tree! {
+            1 => {
+                right: 2,
+            }
+        };
+
+        let dot = Writer::new(&tree, |value| value.to_string())
+            .edge_labels(true)
+            .to_string();
+        assert_eq!(
+            dot,
+            r#"digraph {
+    ordering=out;
+    n0 [label="1"];
+    n0L [style=invis];
+    n0 -> n0L [style=invis];
+    n0 -> n1 [label="R"];
+    n1 [label="2"];
+}"#
+        );
+
+        let dot = Writer::new(&tree, |value| value.to_string())
+            .placeholders(false)
+            .to_string();
+        assert!(!dot.contains("invis"));
+    }
+}
@@ -0,0 +1,226 @@
+//! Entry API for the child slots of a `BinaryNodeMut`.
+
+use crate::binary_tree::{BinaryNodeMut, Side, Slot};
+
+impl<'a, T> BinaryNodeMut<'a, T> {
+    /// Returns the entry for the left child slot.
+    pub fn left_entry(&mut self) -> Entry<'_, T> {
+        self.entry(Side::Left)
+    }
+
+    /// Returns the entry for the right child slot.
+    pub fn right_entry(&mut self) -> Entry<'_, T> {
+        self.entry(Side::Right)
+    }
+
+    /// Returns the entry for the child slot on the given side.
+    pub fn entry(&mut self, side: Side) -> Entry<'_, T> {
+        let mut slot = self.slot(side);
+        if slot.is_vacant() {
+            Entry::Vacant(VacantEntry { slot, side })
+        } else {
+            Entry::Occupied(OccupiedEntry {
+                node: slot.into_node(),
+                side,
+            })
+        }
+    }
+
+    /// Inserts a left child with `value` and returns the node, or gives
+    /// `value` back if there is a left child already.
+    pub fn insert_left(&mut self, value: T) -> Result<BinaryNodeMut<'_, T>, T> {
+        match self.left_entry() {
+            Entry::Vacant(entry) => Ok(entry.insert(value)),
+            Entry::Occupied(_) => Err(value),
+        }
+    }
+
+    /// Inserts a right child with `value` and returns the node, or gives
+    /// `value` back if there is a right child already.
+    pub fn insert_right(&mut self, value: T) -> Result<BinaryNodeMut<'_, T>, T> {
+        match self.right_entry() {
+            Entry::Vacant(entry) => Ok(entry.insert(value)),
+            Entry::Occupied(_) => Err(value),
+        }
+    }
+
+    /// Sets the value of the left child, returning the old value if there was
+    /// a left child. The subtree of an existing left child is kept.
+    pub fn replace_left(&mut self, value: T) -> Option<T> {
+        self.left_entry().replace(value)
+    }
+
+    /// Sets the value of the right child, returning the old value if there
+    /// was a right child. The subtree of an existing right child is kept.
+    pub fn replace_right(&mut self, value: T) -> Option<T> {
+        self.right_entry().replace(value)
+    }
+}
+
+/// View into a child slot of a node, which is either vacant or occupied.
+pub enum Entry<'a, T> {
+    Vacant(VacantEntry<'a, T>),
+    Occupied(OccupiedEntry<'a, T>),
+}
+
+impl<'a, T> Entry<'a, T> {
+    /// Returns which child slot this entry is for.
+    pub fn side(&self) -> Side {
+        match self {
+            Entry::Vacant(entry) => entry.side(),
+            Entry::Occupied(entry) => entry.side(),
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, and returns the child.
+    pub fn or_insert(self, default: T) -> BinaryNodeMut<'a, T> {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `default` if the entry is vacant, and returns
+    /// the child.
+    pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> BinaryNodeMut<'a, T> {
+        match self {
+            Entry::Vacant(entry) => entry.insert(default()),
+            Entry::Occupied(entry) => entry.into_node(),
+        }
+    }
+
+    /// Inserts `T::default()` if the entry is vacant, and returns the child.
+    pub fn or_default(self) -> BinaryNodeMut<'a, T>
+    where
+        T: Default,
+    {
+        self.or_insert_with(T::default)
+    }
+
+    /// Calls `f` on the value of the child if the entry is occupied.
+    pub fn and_modify<F: FnOnce(&mut T)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+
+    fn replace(self, value: T) -> Option<T> {
+        match self {
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+        }
+    }
+}
+
+/// Vacant child slot of a node.
+pub struct VacantEntry<'a, T> {
+    slot: Slot<'a, T>,
+    side: Side,
+}
+
+impl<'a, T> VacantEntry<'a, T> {
+    /// Returns which child slot this entry is for.
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    /// Inserts a child with `value`, returning the child.
+    pub fn insert(self, value: T) -> BinaryNodeMut<'a, T> {
+        self.slot.fill(value)
+    }
+}
+
+/// Occupied child slot of a node.
+pub struct OccupiedEntry<'a, T> {
+    node: BinaryNodeMut<'a, T>,
+    side: Side,
+}
+
+impl<'a, T> OccupiedEntry<'a, T> {
+    /// Returns which child slot this entry is for.
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    /// Returns a mutable reference to the value of the child.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.node.value()
+    }
+
+    /// Converts the entry into a mutable reference to the value of the child.
+    pub fn into_mut(self) -> &'a mut T {
+        self.node.into_value()
+    }
+
+    /// Converts the entry into the child.
+    pub fn into_node(self) -> BinaryNodeMut<'a, T> {
+        self.node
+    }
+
+    /// Replaces the value of the child, returning the old value. The subtree
+    /// of the child is kept.
+    pub fn insert(&mut self, value: T) -> T {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the subtree of the child, returning the value of the child.
+    pub fn remove(mut self) -> T {
+        let mut parent = self.node.parent().expect("has parent");
+        let removed = match self.side {
+            Side::Left => parent.remove_left(),
+            Side::Right => parent.remove_right(),
+        };
+        removed.expect("exists")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BinaryTree;
+
+    #[test]
+    fn insert_and_replace_children_work() {
+        let mut tree = BinaryTree::new(1);
+        let mut root = tree.root_mut();
+
+        assert_eq!(root.insert_left(2).map(|mut n| *n.value()), Ok(2));
+        assert_eq!(root.insert_left(3).err(), Some(3));
+        assert_eq!(root.replace_left(4), Some(2));
+        assert_eq!(root.replace_right(5), None);
+        root.left().unwrap().set_left(6);
+        assert_eq!(root.replace_left(7), Some(4));
+        assert_eq!(
+            format!("{:?}", tree),
+            "1 => { left: 7 => { left: 6 }, right: 5 }"
+        );
+    }
+
+    #[test]
+    fn entries_work() {
+        let mut tree = BinaryTree::new(1);
+        let mut root = tree.root_mut();
+
+        *root.left_entry().or_insert(2).value() += 10;
+        root.left_entry()
+            .and_modify(|value| *value *= 2)
+            .or_insert(0);
+        root.right_entry()
+            .and_modify(|value| *value *= 2)
+            .or_insert_with(|| 3)
+            .left_entry()
+            .or_default();
+        assert_eq!(root.right_entry().side(), Side::Right);
+        assert_eq!(
+            format!("{:?}", tree),
+            "1 => { left: 24, right: 3 => { left: 0 } }"
+        );
+
+        match tree.root_mut().right_entry() {
+            Entry::Occupied(entry) => assert_eq!(entry.remove(), 3),
+            Entry::Vacant(_) => unreachable!(),
+        }
+        assert_eq!(format!("{:?}", tree), "1 => { left: 24 }");
+    }
+}
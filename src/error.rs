@@ -0,0 +1,34 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use crate::binary_tree::{BinaryNodeId, Side};
+
+/// Error returned by the fallible `try_*` operations of `BinaryTree`,
+/// `BinaryNodeRef` and `BinaryNodeMut`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryTreeError {
+    /// No node with the id is in the tree.
+    NotFound(BinaryNodeId),
+    /// The child slot on the given side already holds a node.
+    Occupied(Side),
+    /// The operation requires a child on the given side.
+    MissingChild(Side),
+    /// The node doesn't have the two child slots every node is expected to
+    /// have, the tree's invariants were broken.
+    Corrupted(BinaryNodeId),
+}
+
+impl Display for BinaryTreeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BinaryTreeError::NotFound(id) => write!(f, "node {:?} is not in the tree", id),
+            BinaryTreeError::Occupied(side) => write!(f, "{} child already exists", side),
+            BinaryTreeError::MissingChild(side) => write!(f, "{} child is missing", side),
+            BinaryTreeError::Corrupted(id) => {
+                write!(f, "node {:?} doesn't have two child slots", id)
+            }
+        }
+    }
+}
+
+impl Error for BinaryTreeError {}
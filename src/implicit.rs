@@ -0,0 +1,239 @@
+use std::collections::VecDeque;
+
+use crate::binary_tree::{BinaryNodeId, BinaryTree};
+
+/// Read-only binary tree stored in the implicit array (heap) layout, where the
+/// root is at index 0 and the children of the node at index `i` are at
+/// `2i + 1` and `2i + 2`. Absent nodes are `None`.
+///
+/// Complete trees are stored without gaps. Sparse trees leave holes for every
+/// absent node above the deepest level, so a path-shaped tree of height `h`
+/// takes `2^(h + 1) - 1` slots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImplicitTree<T> {
+    slots: Vec<Option<T>>,
+}
+
+impl<T> ImplicitTree<T> {
+    /// Wraps slots in the implicit array layout. Trailing `None`s are trimmed.
+    ///
+    /// Returns `None` if there is no root, or a present slot has an absent
+    /// parent.
+    pub fn from_heap_array(mut slots: Vec<Option<T>>) -> Option<Self> {
+        while let Some(None) = slots.last() {
+            slots.pop();
+        }
+        slots.first()?.as_ref()?;
+        let orphaned = (1..slots.len()).any(|i| slots[i].is_some() && slots[(i - 1) / 2].is_none());
+        if orphaned {
+            return None;
+        }
+
+        Some(Self { slots })
+    }
+
+    /// Returns the slots in the implicit array layout.
+    pub fn as_heap_array(&self) -> &[Option<T>] {
+        &self.slots
+    }
+
+    /// Converts into the slots in the implicit array layout.
+    pub fn into_heap_array(self) -> Vec<Option<T>> {
+        self.slots
+    }
+
+    /// Returns a reference to the root node.
+    pub fn root(&self) -> ImplicitNodeRef<'_, T> {
+        ImplicitNodeRef {
+            tree: self,
+            index: 0,
+        }
+    }
+
+    fn node(&self, index: usize) -> Option<ImplicitNodeRef<'_, T>> {
+        self.slots.get(index)?.as_ref()?;
+        Some(ImplicitNodeRef { tree: self, index })
+    }
+}
+
+/// Reference to a node of an `ImplicitTree`.
+#[derive(Debug)]
+pub struct ImplicitNodeRef<'a, T> {
+    tree: &'a ImplicitTree<T>,
+    index: usize,
+}
+
+impl<'a, T> Copy for ImplicitNodeRef<'a, T> {}
+impl<'a, T> Clone for ImplicitNodeRef<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> ImplicitNodeRef<'a, T> {
+    /// Return the left child, if exists.
+    pub fn left(&self) -> Option<ImplicitNodeRef<'a, T>> {
+        self.tree.node(self.index.checked_mul(2)?.checked_add(1)?)
+    }
+
+    /// Return the right child, if exists.
+    pub fn right(&self) -> Option<ImplicitNodeRef<'a, T>> {
+        self.tree.node(self.index.checked_mul(2)?.checked_add(2)?)
+    }
+
+    /// Return the parent, or `None` for the root.
+    pub fn parent(&self) -> Option<ImplicitNodeRef<'a, T>> {
+        if self.index == 0 {
+            return None;
+        }
+        self.tree.node((self.index - 1) / 2)
+    }
+
+    /// Get the value for this node.
+    pub fn value(&self) -> &'a T {
+        self.tree.slots[self.index].as_ref().expect("exists")
+    }
+
+    /// Returns the position of this node in the implicit array layout.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<T> BinaryTree<T> {
+    /// Builds a tree from slots in the implicit array layout, see
+    /// `ImplicitTree`.
+    ///
+    /// Returns `None` if there is no root, or a present slot has an absent
+    /// parent.
+    pub fn from_heap_array(slots: Vec<Option<T>>) -> Option<Self> {
+        ImplicitTree::from_heap_array(slots).map(BinaryTree::from)
+    }
+
+    /// Converts the tree into slots in the implicit array layout, see
+    /// `ImplicitTree`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree is so deep that slot indices overflow `usize`.
+    pub fn to_heap_array(&self) -> Vec<Option<T>>
+    where
+        T: Clone,
+    {
+        let mut slots = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back((self.root(), 0usize));
+        while let Some((node, index)) = queue.pop_front() {
+            if slots.len() <= index {
+                slots.resize_with(index + 1, || None);
+            }
+            slots[index] = Some(node.value().clone());
+
+            let left_index = index
+                .checked_mul(2)
+                .and_then(|index| index.checked_add(1))
+                .expect("tree too deep for the implicit layout");
+            queue.extend(node.left().map(|left| (left, left_index)));
+            queue.extend(node.right().map(|right| (right, left_index + 1)));
+        }
+        slots
+    }
+
+    /// Takes a read-only snapshot of the tree in the implicit array layout.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree is so deep that slot indices overflow `usize`.
+    pub fn to_implicit(&self) -> ImplicitTree<T>
+    where
+        T: Clone,
+    {
+        ImplicitTree {
+            slots: self.to_heap_array(),
+        }
+    }
+}
+
+impl<T> From<ImplicitTree<T>> for BinaryTree<T> {
+    fn from(implicit: ImplicitTree<T>) -> Self {
+        let mut slots = implicit.slots.into_iter();
+        let mut tree = BinaryTree::new(slots.next().flatten().expect("has root"));
+
+        // Ids of the nodes created so far, by slot index.
+        let mut ids: Vec<Option<BinaryNodeId>> = vec![Some(tree.root().id())];
+        for (index, value) in (1..).zip(slots) {
+            let id = value.map(|value| {
+                let parent_id = ids[(index - 1) / 2].expect("validated");
+                let mut parent = tree.get_mut(parent_id).expect("exists");
+                if index % 2 == 1 {
+                    parent.set_left(value).id()
+                } else {
+                    parent.set_right(value).id()
+                }
+            });
+            ids.push(id);
+        }
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heap_array_round_trip_works() {
+        let tree = binary_tree! {
+            1 => {
+                left: 2 => {
+                    right: 5,
+                },
+                right: 3,
+            }
+        };
+
+        let slots = tree.to_heap_array();
+        assert_eq!(slots, vec![Some(1), Some(2), Some(3), None, Some(5)]);
+
+        let rebuilt = BinaryTree::from_heap_array(slots).unwrap();
+        assert_eq!(format!("{:?}", rebuilt), format!("{:?}", tree));
+
+        let complete = BinaryTree::from_heap_array((1..=6).map(Some).collect()).unwrap();
+        assert!(complete.is_complete());
+        assert_eq!(complete.len(), 6);
+    }
+
+    #[test]
+    fn invalid_heap_arrays_are_rejected() {
+        assert!(BinaryTree::<i32>::from_heap_array(vec![]).is_none());
+        assert!(BinaryTree::from_heap_array(vec![None, Some(1)]).is_none());
+        assert!(BinaryTree::from_heap_array(vec![Some(1), None, Some(2), Some(3)]).is_none());
+        assert!(ImplicitTree::from_heap_array(vec![Some(1), None, Some(2), None, None]).is_some());
+    }
+
+    #[test]
+    fn implicit_navigation_works() {
+        let tree = binary_tree! {
+            'a' => {
+                right: 'c' => {
+                    left: 'f',
+                },
+            }
+        };
+
+        let implicit = tree.to_implicit();
+        assert_eq!(
+            implicit.as_heap_array(),
+            &[Some('a'), None, Some('c'), None, None, Some('f')]
+        );
+
+        let root = implicit.root();
+        assert!(root.left().is_none());
+        let c = root.right().unwrap();
+        assert_eq!(c.value(), &'c');
+        let f = c.left().unwrap();
+        assert_eq!((f.value(), f.index()), (&'f', 5));
+        assert_eq!(f.parent().map(|n| *n.value()), Some('c'));
+        assert!(root.parent().is_none());
+    }
+}
@@ -0,0 +1,393 @@
+use std::collections::VecDeque;
+use std::vec;
+
+use crate::binary_tree::BinaryNodeRef;
+
+/// Iterator over a subtree in pre-order (node, left, right).
+#[derive(Debug)]
+pub struct Preorder<'a, T> {
+    stack: Vec<BinaryNodeRef<'a, T>>,
+}
+
+impl<'a, T> Preorder<'a, T> {
+    pub(crate) fn new(root: BinaryNodeRef<'a, T>) -> Self {
+        Self { stack: vec![root] }
+    }
+}
+
+impl<'a, T> Clone for Preorder<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            stack: self.stack.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Preorder<'a, T> {
+    type Item = BinaryNodeRef<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        // Push right first so the left subtree is visited first.
+        self.stack.extend(node.right());
+        self.stack.extend(node.left());
+        Some(node)
+    }
+}
+
+/// Iterator over a subtree in in-order (left, node, right).
+#[derive(Debug)]
+pub struct Inorder<'a, T> {
+    stack: Vec<BinaryNodeRef<'a, T>>,
+}
+
+impl<'a, T> Inorder<'a, T> {
+    pub(crate) fn new(root: BinaryNodeRef<'a, T>) -> Self {
+        let mut iter = Self { stack: Vec::new() };
+        iter.push_left_spine(Some(root));
+        iter
+    }
+
+    fn push_left_spine(&mut self, mut node: Option<BinaryNodeRef<'a, T>>) {
+        while let Some(current) = node {
+            self.stack.push(current);
+            node = current.left();
+        }
+    }
+}
+
+impl<'a, T> Clone for Inorder<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            stack: self.stack.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Inorder<'a, T> {
+    type Item = BinaryNodeRef<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left_spine(node.right());
+        Some(node)
+    }
+}
+
+/// Iterator over a subtree in post-order (left, right, node).
+#[derive(Debug)]
+pub struct Postorder<'a, T> {
+    // Each node is paired with whether its children have already been pushed.
+    stack: Vec<(BinaryNodeRef<'a, T>, bool)>,
+}
+
+impl<'a, T> Postorder<'a, T> {
+    pub(crate) fn new(root: BinaryNodeRef<'a, T>) -> Self {
+        Self {
+            stack: vec![(root, false)],
+        }
+    }
+}
+
+impl<'a, T> Clone for Postorder<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            stack: self.stack.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Postorder<'a, T> {
+    type Item = BinaryNodeRef<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, expanded) = self.stack.pop()?;
+            if expanded {
+                return Some(node);
+            }
+
+            self.stack.push((node, true));
+            self.stack.extend(node.right().map(|right| (right, false)));
+            self.stack.extend(node.left().map(|left| (left, false)));
+        }
+    }
+}
+
+/// Iterator over a subtree in level-order (breadth-first, left to right).
+#[derive(Debug)]
+pub struct LevelOrder<'a, T> {
+    inner: LevelOrderWithDepth<'a, T>,
+}
+
+impl<'a, T> LevelOrder<'a, T> {
+    pub(crate) fn new(root: BinaryNodeRef<'a, T>) -> Self {
+        Self {
+            inner: LevelOrderWithDepth::new(root),
+        }
+    }
+}
+
+impl<'a, T> Clone for LevelOrder<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for LevelOrder<'a, T> {
+    type Item = BinaryNodeRef<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_depth, node)| node)
+    }
+}
+
+/// Iterator over a subtree in level-order, yielding each node with its depth
+/// relative to the node the iterator was started from.
+#[derive(Debug)]
+pub struct LevelOrderWithDepth<'a, T> {
+    queue: VecDeque<(usize, BinaryNodeRef<'a, T>)>,
+}
+
+impl<'a, T> LevelOrderWithDepth<'a, T> {
+    pub(crate) fn new(root: BinaryNodeRef<'a, T>) -> Self {
+        let mut queue = VecDeque::new();
+        queue.push_back((0, root));
+        Self { queue }
+    }
+}
+
+impl<'a, T> Clone for LevelOrderWithDepth<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for LevelOrderWithDepth<'a, T> {
+    type Item = (usize, BinaryNodeRef<'a, T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, node) = self.queue.pop_front()?;
+        self.queue.extend(node.left().map(|left| (depth + 1, left)));
+        self.queue
+            .extend(node.right().map(|right| (depth + 1, right)));
+        Some((depth, node))
+    }
+}
+
+/// Iterator over a subtree one level at a time, yielding the nodes of each
+/// level from left to right.
+#[derive(Debug)]
+pub struct Levels<'a, T> {
+    level: Vec<BinaryNodeRef<'a, T>>,
+}
+
+impl<'a, T> Levels<'a, T> {
+    pub(crate) fn new(root: BinaryNodeRef<'a, T>) -> Self {
+        Self { level: vec![root] }
+    }
+}
+
+impl<'a, T> Clone for Levels<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            level: self.level.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Levels<'a, T> {
+    type Item = Vec<BinaryNodeRef<'a, T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.level.is_empty() {
+            return None;
+        }
+
+        let next_level = self
+            .level
+            .iter()
+            .flat_map(|node| node.left().into_iter().chain(node.right()))
+            .collect();
+        Some(std::mem::replace(&mut self.level, next_level))
+    }
+}
+
+/// Iterator over the ancestors of a node, from its parent up to the root.
+#[derive(Debug)]
+pub struct Ancestors<'a, T> {
+    next: Option<BinaryNodeRef<'a, T>>,
+}
+
+impl<'a, T> Ancestors<'a, T> {
+    pub(crate) fn new(parent: Option<BinaryNodeRef<'a, T>>) -> Self {
+        Self { next: parent }
+    }
+}
+
+impl<'a, T> Clone for Ancestors<'a, T> {
+    fn clone(&self) -> Self {
+        Self { next: self.next }
+    }
+}
+
+impl<'a, T> Iterator for Ancestors<'a, T> {
+    type Item = BinaryNodeRef<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next?;
+        self.next = node.parent();
+        Some(node)
+    }
+}
+
+/// Iterator over mutable references to the values of a tree, in the order of
+/// one of its traversals.
+#[derive(Debug)]
+pub struct ValuesMut<'a, T> {
+    values: vec::IntoIter<&'a mut T>,
+}
+
+impl<'a, T> ValuesMut<'a, T> {
+    pub(crate) fn new(values: Vec<&'a mut T>) -> Self {
+        Self {
+            values: values.into_iter(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for ValuesMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.values.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for ValuesMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.values.next_back()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for ValuesMut<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::BinaryTree;
+
+    fn values<'a>(iter: impl Iterator<Item = crate::BinaryNodeRef<'a, char>>) -> String {
+        iter.map(|node| *node.value()).collect()
+    }
+
+    #[test]
+    fn depth_first_orders_work() {
+        let tree = binary_tree! {
+            'a' => {
+                left: 'b' => {
+                    left: 'd',
+                    right: 'e',
+                },
+                right: 'c' => {
+                    right: 'f',
+                },
+            }
+        };
+
+        assert_eq!(values(tree.preorder()), "abdecf");
+        assert_eq!(values(tree.inorder()), "dbeacf");
+        assert_eq!(values(tree.postorder()), "debfca");
+
+        let c = tree.root().right().unwrap();
+        assert_eq!(values(c.preorder()), "cf");
+        assert_eq!(values(c.inorder()), "cf");
+        assert_eq!(values(c.postorder()), "fc");
+    }
+
+    #[test]
+    fn level_orders_work() {
+        let tree = binary_tree! {
+            'a' => {
+                left: 'b' => {
+                    right: 'd',
+                },
+                right: 'c' => {
+                    left: 'e',
+                    right: 'f',
+                },
+            }
+        };
+
+        assert_eq!(values(tree.level_order()), "abcdef");
+
+        let depths: Vec<_> = tree
+            .level_order_with_depth()
+            .map(|(depth, node)| (depth, *node.value()))
+            .collect();
+        assert_eq!(
+            depths,
+            vec![(0, 'a'), (1, 'b'), (1, 'c'), (2, 'd'), (2, 'e'), (2, 'f')]
+        );
+
+        let levels: Vec<String> = tree
+            .levels()
+            .map(|level| values(level.into_iter()))
+            .collect();
+        assert_eq!(levels, vec!["a", "bc", "def"]);
+    }
+
+    #[test]
+    fn values_mut_works() {
+        let mut tree = binary_tree! {
+            'a' => {
+                left: 'b' => {
+                    left: 'd',
+                    right: 'x',
+                },
+                right: 'c',
+            }
+        };
+        // Removed nodes are freed and must not be handed out.
+        tree.root_mut().left().unwrap().remove_right();
+
+        let visit = |values: crate::ValuesMut<'_, char>| -> String {
+            values
+                .map(|value| {
+                    *value = value.to_ascii_uppercase();
+                    *value
+                })
+                .collect()
+        };
+        assert_eq!(visit(tree.preorder_values_mut()), "ABDC");
+        assert_eq!(visit(tree.inorder_values_mut()), "DBAC");
+        assert_eq!(visit(tree.postorder_values_mut()), "DBCA");
+        assert_eq!(visit(tree.level_order_values_mut()), "ABCD");
+
+        for value in tree.values_mut() {
+            *value = value.to_ascii_lowercase();
+        }
+        assert_eq!(values(tree.preorder()), "abdc");
+    }
+
+    #[test]
+    fn deep_tree_does_not_overflow() {
+        let mut tree = BinaryTree::new(0);
+        let mut id = tree.root().id();
+        for i in 1..100_000 {
+            id = tree.get_mut(id).unwrap().set_left(i).id();
+        }
+
+        assert_eq!(tree.preorder().count(), 100_000);
+        assert_eq!(tree.inorder().next().map(|n| *n.value()), Some(99_999));
+        assert_eq!(tree.postorder().last().map(|n| *n.value()), Some(0));
+        assert_eq!(tree.levels().count(), 100_000);
+    }
+}
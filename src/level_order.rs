@@ -0,0 +1,96 @@
+use std::collections::VecDeque;
+
+use crate::binary_tree::BinaryTree;
+
+impl<T> BinaryTree<T> {
+    /// Builds a tree from the compact level-order format used by LeetCode,
+    /// e.g. `[1, null, 2, 3]`: the root comes first, then the left and right
+    /// child slots of every present node in level-order, with `None` marking
+    /// an absent child. Trailing `None`s may be left out.
+    ///
+    /// Returns `None` if the sequence is empty or starts with `None`. Values
+    /// after the last child slot of the tree are ignored.
+    pub fn from_level_order<I: IntoIterator<Item = Option<T>>>(values: I) -> Option<Self> {
+        let mut values = values.into_iter();
+        let mut tree = BinaryTree::new(values.next()??);
+
+        let mut queue = VecDeque::new();
+        queue.push_back(tree.root().id());
+        while let Some(id) = queue.pop_front() {
+            let mut node = tree.get_mut(id).expect("exists");
+            match values.next() {
+                Some(Some(left)) => queue.push_back(node.set_left(left).id()),
+                Some(None) => {}
+                None => break,
+            }
+            match values.next() {
+                Some(Some(right)) => queue.push_back(node.set_right(right).id()),
+                Some(None) => {}
+                None => break,
+            }
+        }
+        Some(tree)
+    }
+
+    /// Converts the tree into the compact level-order format read by
+    /// `from_level_order`, with trailing `None`s trimmed.
+    pub fn to_level_order(&self) -> Vec<Option<T>>
+    where
+        T: Clone,
+    {
+        let mut values = vec![Some(self.root().value().clone())];
+        for node in self.level_order() {
+            values.push(node.left().map(|left| left.value().clone()));
+            values.push(node.right().map(|right| right.value().clone()));
+        }
+
+        while let Some(None) = values.last() {
+            values.pop();
+        }
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BinaryTree;
+
+    #[test]
+    fn level_order_round_trip_works() {
+        // [5, 4, 8, 11, null, 13, 4, 7, 2, null, null, null, 1]
+        let values = vec![
+            Some(5),
+            Some(4),
+            Some(8),
+            Some(11),
+            None,
+            Some(13),
+            Some(4),
+            Some(7),
+            Some(2),
+            None,
+            None,
+            None,
+            Some(1),
+        ];
+
+        let tree = BinaryTree::from_level_order(values.clone()).unwrap();
+        assert_eq!(
+            format!("{:?}", tree),
+            "5 => { left: 4 => { left: 11 => { left: 7, right: 2 } }, \
+             right: 8 => { left: 13, right: 4 => { right: 1 } } }"
+        );
+        assert_eq!(tree.to_level_order(), values);
+    }
+
+    #[test]
+    fn right_only_and_empty_inputs_work() {
+        let tree = BinaryTree::from_level_order(vec![Some(1), None, Some(2), Some(3)]).unwrap();
+        assert_eq!(format!("{:?}", tree), "1 => { right: 2 => { left: 3 } }");
+        assert_eq!(tree.to_level_order(), vec![Some(1), None, Some(2), Some(3)]);
+
+        assert!(BinaryTree::<i32>::from_level_order(vec![]).is_none());
+        assert!(BinaryTree::<i32>::from_level_order(vec![None, Some(1)]).is_none());
+        assert_eq!(binary_tree!(1).to_level_order(), vec![Some(1)]);
+    }
+}
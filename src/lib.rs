@@ -1,4 +1,34 @@
-mod binary_tree;
+#[macro_use]
 mod macros;
 
-pub use crate::binary_tree::{BinaryNodeMut, BinaryNodeRef, BinaryTree};
+pub mod avl;
+mod binary_tree;
+pub mod bst;
+mod cmp;
+mod display;
+pub mod dot;
+pub mod entry;
+mod error;
+mod implicit;
+mod iter;
+mod level_order;
+mod map;
+pub mod rb;
+mod reconstruct;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod shape;
+mod zip;
+
+pub use crate::avl::{AvlTreeMap, AvlTreeSet};
+pub use crate::binary_tree::{BinaryNodeId, BinaryNodeMut, BinaryNodeRef, BinaryTree, Side};
+pub use crate::bst::BinarySearchTree;
+pub use crate::display::Pretty;
+pub use crate::error::BinaryTreeError;
+pub use crate::implicit::{ImplicitNodeRef, ImplicitTree};
+pub use crate::iter::{
+    Ancestors, Inorder, LevelOrder, LevelOrderWithDepth, Levels, Postorder, Preorder, ValuesMut,
+};
+pub use crate::rb::{RbTreeMap, RbTreeSet};
+pub use crate::reconstruct::TraversalError;
+pub use crate::zip::ShapeMismatch;
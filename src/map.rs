@@ -0,0 +1,209 @@
+use std::convert::Infallible;
+
+use crate::binary_tree::{BinaryNodeId, BinaryNodeRef, BinaryTree, Side};
+
+impl<T> BinaryTree<T> {
+    /// Converts the tree into one of the same shape, with `f` applied to every
+    /// value in pre-order.
+    pub fn map<U, F: FnMut(T) -> U>(self, mut f: F) -> BinaryTree<U> {
+        match self.try_map(|value| Ok::<_, Infallible>(f(value))) {
+            Ok(tree) => tree,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Returns a tree of the same shape, with `f` applied to every value in
+    /// pre-order.
+    pub fn map_ref<U, F: FnMut(&T) -> U>(&self, mut f: F) -> BinaryTree<U> {
+        let nodes = shape(self);
+        let built = build(&nodes, |id| {
+            let value = self.get(id).expect("exists").value();
+            Ok::<_, Infallible>(Some(f(value)))
+        });
+        match built {
+            Ok(tree) => tree.expect("root is kept"),
+            Err(never) => match never {},
+        }
+    }
+
+    /// Like `map`, but stops at the first error `f` returns.
+    pub fn try_map<U, E, F: FnMut(T) -> Result<U, E>>(
+        mut self,
+        mut f: F,
+    ) -> Result<BinaryTree<U>, E> {
+        let nodes = shape(&self);
+        let tree = build(&nodes, |id| f(self.take_value(id)).map(Some))?;
+        Ok(tree.expect("root is kept"))
+    }
+
+    /// Converts the tree with `f` applied to every value in pre-order. Nodes
+    /// for which `f` returns `None` are dropped along with their subtrees,
+    /// whose values `f` doesn't see. Returns `None` if the root is dropped.
+    pub fn filter_map<U, F: FnMut(T) -> Option<U>>(mut self, mut f: F) -> Option<BinaryTree<U>> {
+        let nodes = shape(&self);
+        let built = build(&nodes, |id| Ok::<_, Infallible>(f(self.take_value(id))));
+        match built {
+            Ok(tree) => tree,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Folds the tree bottom-up, see `BinaryNodeRef::fold`.
+    pub fn fold<R, L, C>(&self, leaf: L, combine: C) -> R
+    where
+        L: FnMut() -> R,
+        C: FnMut(&T, R, R) -> R,
+    {
+        self.root().fold(leaf, combine)
+    }
+}
+
+impl<'a, T> BinaryNodeRef<'a, T> {
+    /// Folds this subtree bottom-up: every absent child is replaced with the
+    /// result of `leaf`, and every node with the result of `combine` applied
+    /// to its value and the results of its left and right child.
+    ///
+    /// For example, `fold(|| 0, |_, left, right| 1 + left.max(right))`
+    /// counts the nodes on the longest path down from this node.
+    pub fn fold<R, L, C>(&self, mut leaf: L, mut combine: C) -> R
+    where
+        L: FnMut() -> R,
+        C: FnMut(&T, R, R) -> R,
+    {
+        // Post-order leaves the results of a node's left and right subtrees
+        // on top of the stack right before the node itself is visited.
+        let mut results = Vec::new();
+        for node in self.postorder() {
+            let right = match node.right() {
+                Some(_) => results.pop().expect("right result"),
+                None => leaf(),
+            };
+            let left = match node.left() {
+                Some(_) => results.pop().expect("left result"),
+                None => leaf(),
+            };
+            results.push(combine(node.value(), left, right));
+        }
+        results.pop().expect("root result")
+    }
+}
+
+/// Nodes of a tree in pre-order, each with the position of its parent in the
+/// list and the side it hangs off of it.
+pub(crate) fn shape<T>(tree: &BinaryTree<T>) -> Vec<(BinaryNodeId, Option<(usize, Side)>)> {
+    let mut nodes = Vec::new();
+    let mut stack = vec![(tree.root(), None)];
+    while let Some((node, parent)) = stack.pop() {
+        let position = nodes.len();
+        nodes.push((node.id(), parent));
+        stack.extend(
+            node.right()
+                .map(|right| (right, Some((position, Side::Right)))),
+        );
+        stack.extend(node.left().map(|left| (left, Some((position, Side::Left)))));
+    }
+    nodes
+}
+
+/// Builds a tree of the given shape with the values returned by `value` for
+/// each node, in pre-order. Nodes for which `value` returns `None` are left
+/// out along with their subtrees, without calling `value` for them.
+pub(crate) fn build<U, E, F>(
+    nodes: &[(BinaryNodeId, Option<(usize, Side)>)],
+    mut value: F,
+) -> Result<Option<BinaryTree<U>>, E>
+where
+    F: FnMut(BinaryNodeId) -> Result<Option<U>, E>,
+{
+    let mut tree: Option<BinaryTree<U>> = None;
+    // Ids of the nodes in the new tree, by position in `nodes`.
+    let mut ids: Vec<Option<BinaryNodeId>> = Vec::with_capacity(nodes.len());
+    for &(id, parent) in nodes {
+        let new_id = match parent {
+            None => value(id)?.map(|value| {
+                let root = tree.insert(BinaryTree::new(value));
+                root.root().id()
+            }),
+            Some((position, side)) => match ids[position] {
+                Some(parent_id) => value(id)?.map(|value| {
+                    let tree = tree.as_mut().expect("root is kept");
+                    let mut parent = tree.get_mut(parent_id).expect("exists");
+                    match side {
+                        Side::Left => parent.set_left(value).id(),
+                        Side::Right => parent.set_right(value).id(),
+                    }
+                }),
+                None => None,
+            },
+        };
+        ids.push(new_id);
+    }
+    Ok(tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BinaryTree;
+
+    fn expression() -> BinaryTree<&'static str> {
+        binary_tree! {
+            "*" => {
+                left: "+" => {
+                    left: "1",
+                    right: "2",
+                },
+                right: "3",
+            }
+        }
+    }
+
+    #[test]
+    fn maps_preserve_shape() {
+        let tree = expression();
+
+        let lengths = tree.map_ref(|value| value.len() * 10);
+        assert_eq!(
+            format!("{:?}", lengths),
+            "10 => { left: 10 => { left: 10, right: 10 }, right: 10 }"
+        );
+
+        let mut visited = Vec::new();
+        let owned = tree.map(|value| {
+            visited.push(value);
+            value.to_string()
+        });
+        assert_eq!(visited, vec!["*", "+", "1", "2", "3"]);
+        assert_eq!(
+            format!("{:?}", owned),
+            r#""*" => { left: "+" => { left: "1", right: "2" }, right: "3" }"#
+        );
+
+        let parsed = expression().try_map(|value| value.parse::<i32>());
+        assert!(parsed.is_err());
+
+        let numbers = expression().filter_map(|value| value.parse::<i32>().ok());
+        assert!(numbers.is_none());
+        let operators = expression().filter_map(|value| value.parse::<i32>().err().map(|_| value));
+        assert_eq!(
+            format!("{:?}", operators.unwrap()),
+            r#""*" => { left: "+" }"#
+        );
+    }
+
+    #[test]
+    fn fold_works() {
+        let tree = expression();
+        let value = tree.fold(
+            || None,
+            |op, left: Option<i32>, right| match (left, right) {
+                (Some(left), Some(right)) if *op == "+" => Some(left + right),
+                (Some(left), Some(right)) => Some(left * right),
+                _ => op.parse().ok(),
+            },
+        );
+        assert_eq!(value, Some(9));
+
+        let longest_path = tree.fold(|| 0, |_, left, right| 1 + left.max(right));
+        assert_eq!(longest_path, tree.height() + 1);
+    }
+}
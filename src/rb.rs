@@ -0,0 +1,961 @@
+//! Red-black tree map and set built on `BinaryTree`.
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
+
+use crate::binary_tree::{BinaryNodeId, BinaryNodeRef, BinaryTree};
+use crate::bst::{self, entry, Keyed, Search};
+use crate::iter::Inorder;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Color {
+    Red,
+    Black,
+}
+
+struct RbNode<K, V> {
+    key: K,
+    value: V,
+    color: Color,
+}
+
+impl<K, V> Keyed for RbNode<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn key(&self) -> &K {
+        &self.key
+    }
+
+    fn value(&self) -> &V {
+        &self.value
+    }
+}
+
+type Tree<K, V> = BinaryTree<RbNode<K, V>>;
+
+/// Ordered map backed by a red-black tree.
+///
+/// The common methods mirror `std::collections::BTreeMap`. Insertion and
+/// removal perform at most two and three rotations respectively.
+pub struct RbTreeMap<K, V> {
+    tree: Option<Tree<K, V>>,
+    len: usize,
+}
+
+impl<K: Ord, V> RbTreeMap<K, V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self { tree: None, len: 0 }
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a key-value pair, returning the previous value for the key.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Returns the entry for the key, for in-place manipulation.
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        let search = self.tree.as_ref().map(|tree| bst::search(tree, &key));
+        match search {
+            Some(Search::Found(id)) => Entry::Occupied(OccupiedEntry { map: self, id }),
+            Some(Search::Vacant(parent_id, ordering)) => Entry::Vacant(VacantEntry {
+                map: self,
+                key,
+                parent: Some((parent_id, ordering)),
+            }),
+            None => Entry::Vacant(VacantEntry {
+                map: self,
+                key,
+                parent: None,
+            }),
+        }
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<BinaryNodeId>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match bst::search(self.tree.as_ref()?, key) {
+            Search::Found(id) => Some(id),
+            Search::Vacant(..) => None,
+        }
+    }
+
+    /// Returns a reference to the value for the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).map(|(_key, value)| value)
+    }
+
+    /// Returns references to the stored key and the value for the key.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let id = self.find(key)?;
+        self.tree.as_ref()?.get(id).map(entry)
+    }
+
+    /// Returns a mutable reference to the value for the key.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let id = self.find(key)?;
+        let node = self.tree.as_mut()?.get_mut(id)?;
+        Some(&mut node.into_value().value)
+    }
+
+    /// Returns true if the map contains the key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Removes the key, returning its value if it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_key, value)| value)
+    }
+
+    /// Removes the key, returning the stored key and value if it was present.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let id = self.find(key)?;
+        Some(self.remove_id(id))
+    }
+
+    fn remove_id(&mut self, id: BinaryNodeId) -> (K, V) {
+        self.len -= 1;
+        if self.len == 0 {
+            let node = self.tree.take().expect("exists").into_root_value();
+            return (node.key, node.value);
+        }
+        let tree = self.tree.as_mut().expect("exists");
+
+        // With two children, the in-order successor (which has no left child)
+        // is spliced out instead and its entry moved into the removed node.
+        let node = tree.get(id).expect("exists");
+        let spliced = match (node.left(), node.right()) {
+            (Some(_), Some(right)) => bst::leftmost(right),
+            _ => node,
+        };
+        let spliced_id = spliced.id();
+        let child_id = spliced.left().or_else(|| spliced.right()).map(|n| n.id());
+        let parent_id = spliced.parent().map(|parent| parent.id());
+        let spliced_was_left = spliced.is_left_child();
+
+        let spliced = tree.get_mut(spliced_id).expect("exists").splice_out();
+        if spliced.color == Color::Black {
+            remove_fixup(tree, child_id, parent_id, spliced_was_left);
+        }
+
+        if spliced_id == id {
+            (spliced.key, spliced.value)
+        } else {
+            let node = tree.get_mut(id).expect("exists").into_value();
+            (
+                std::mem::replace(&mut node.key, spliced.key),
+                std::mem::replace(&mut node.value, spliced.value),
+            )
+        }
+    }
+
+    /// Returns the entry with the smallest key.
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.tree
+            .as_ref()
+            .map(|tree| entry(bst::leftmost(tree.root())))
+    }
+
+    /// Returns the entry with the largest key.
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.tree
+            .as_ref()
+            .map(|tree| entry(bst::rightmost(tree.root())))
+    }
+
+    /// Returns an iterator over the entries in ascending key order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.tree.as_ref().map(BinaryTree::inorder),
+        }
+    }
+
+    /// Returns an iterator over the entries whose keys fall in `range`, in
+    /// ascending key order. An empty or inverted range yields nothing.
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let empty = Range {
+            next: None,
+            last: None,
+        };
+        let tree = match &self.tree {
+            Some(tree) => tree,
+            None => return empty,
+        };
+
+        let first = match range.start_bound() {
+            Bound::Unbounded => Some(bst::leftmost(tree.root())),
+            Bound::Included(key) => bst::ceiling(tree, key),
+            Bound::Excluded(key) => {
+                bst::ceiling(tree, key).and_then(|node| match node.value().key.borrow().cmp(key) {
+                    Ordering::Equal => successor(node),
+                    _ => Some(node),
+                })
+            }
+        };
+        let last = match range.end_bound() {
+            Bound::Unbounded => Some(bst::rightmost(tree.root())),
+            Bound::Included(key) => bst::floor(tree, key),
+            Bound::Excluded(key) => {
+                bst::floor(tree, key).and_then(|node| match node.value().key.borrow().cmp(key) {
+                    Ordering::Equal => predecessor(node),
+                    _ => Some(node),
+                })
+            }
+        };
+
+        match (first, last) {
+            (Some(first), Some(last)) if first.value().key <= last.value().key => Range {
+                next: Some(first),
+                last: Some(last),
+            },
+            _ => empty,
+        }
+    }
+
+    /// Checks that keys are in ascending order, that the root is black, that
+    /// no red node has a red child and that every path from a node down to an
+    /// empty slot passes through the same number of black nodes. Describes the
+    /// first violation found.
+    pub fn validate(&self) -> Result<(), String> {
+        bst::validate_order(self.tree.as_ref(), self.len)?;
+        let tree = match &self.tree {
+            Some(tree) => tree,
+            None => return Ok(()),
+        };
+
+        if tree.root().value().color != Color::Black {
+            return Err("root is red".to_string());
+        }
+
+        // Black heights of already visited subtrees, counting empty slots as
+        // 1. In post-order the children of a node are on top of the stack.
+        let mut black_heights: Vec<usize> = Vec::new();
+        for node in tree.postorder() {
+            let right = match node.right() {
+                Some(_) => black_heights.pop().expect("visited"),
+                None => 1,
+            };
+            let left = match node.left() {
+                Some(_) => black_heights.pop().expect("visited"),
+                None => 1,
+            };
+
+            if left != right {
+                return Err(format!(
+                    "unequal black heights below node at depth {}",
+                    node.depth()
+                ));
+            }
+            let color = node.value().color;
+            if color == Color::Red && (is_red(node.left()) || is_red(node.right())) {
+                return Err(format!("red node with red child at depth {}", node.depth()));
+            }
+            black_heights.push(left + (color == Color::Black) as usize);
+        }
+        Ok(())
+    }
+}
+
+impl<K: Ord, V> Default for RbTreeMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, K: Ord, V> IntoIterator for &'a RbTreeMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// View into a single entry of an `RbTreeMap`, which is either vacant or
+/// occupied.
+pub enum Entry<'a, K: Ord, V> {
+    Vacant(VacantEntry<'a, K, V>),
+    Occupied(OccupiedEntry<'a, K, V>),
+}
+
+impl<'a, K: Ord, V> Entry<'a, K, V> {
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Vacant(entry) => entry.key(),
+            Entry::Occupied(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, and returns a mutable
+    /// reference to the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `default` if the entry is vacant, and returns a
+    /// mutable reference to the value.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Vacant(entry) => entry.insert(default()),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// Inserts `V::default()` if the entry is vacant, and returns a mutable
+    /// reference to the value.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Calls `f` on the value if the entry is occupied.
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+/// Vacant entry of an `RbTreeMap`.
+pub struct VacantEntry<'a, K: Ord, V> {
+    map: &'a mut RbTreeMap<K, V>,
+    key: K,
+    /// Node the key is inserted below, or `None` if the map is empty.
+    parent: Option<(BinaryNodeId, Ordering)>,
+}
+
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Takes ownership of the key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Inserts the value, returning a mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let map = self.map;
+        let node = RbNode {
+            key: self.key,
+            value,
+            color: Color::Red,
+        };
+
+        map.len += 1;
+        let id = match self.parent {
+            Some((parent_id, ordering)) => {
+                let tree = map.tree.as_mut().expect("exists");
+                let mut parent = tree.get_mut(parent_id).expect("exists");
+                let id = if ordering == Ordering::Less {
+                    parent.set_left(node).id()
+                } else {
+                    parent.set_right(node).id()
+                };
+                insert_fixup(tree, id);
+                id
+            }
+            None => {
+                let tree = map.tree.get_or_insert(BinaryTree::new(RbNode {
+                    color: Color::Black,
+                    ..node
+                }));
+                tree.root().id()
+            }
+        };
+
+        let tree = map.tree.as_mut().expect("exists");
+        &mut tree.get_mut(id).expect("exists").into_value().value
+    }
+}
+
+/// Occupied entry of an `RbTreeMap`.
+pub struct OccupiedEntry<'a, K: Ord, V> {
+    map: &'a mut RbTreeMap<K, V>,
+    id: BinaryNodeId,
+}
+
+impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
+    fn node(&self) -> &RbNode<K, V> {
+        let tree = self.map.tree.as_ref().expect("exists");
+        tree.get(self.id).expect("exists").value()
+    }
+
+    fn node_mut(&mut self) -> &mut RbNode<K, V> {
+        let tree = self.map.tree.as_mut().expect("exists");
+        tree.get_mut(self.id).expect("exists").into_value()
+    }
+
+    /// Returns the key of this entry.
+    pub fn key(&self) -> &K {
+        &self.node().key
+    }
+
+    /// Returns a reference to the value of this entry.
+    pub fn get(&self) -> &V {
+        &self.node().value
+    }
+
+    /// Returns a mutable reference to the value of this entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.node_mut().value
+    }
+
+    /// Converts the entry into a mutable reference to its value.
+    pub fn into_mut(self) -> &'a mut V {
+        let tree = self.map.tree.as_mut().expect("exists");
+        &mut tree.get_mut(self.id).expect("exists").into_value().value
+    }
+
+    /// Replaces the value of this entry, returning the old value.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry from the map, returning its value.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Removes the entry from the map, returning the stored key and value.
+    pub fn remove_entry(self) -> (K, V) {
+        self.map.remove_id(self.id)
+    }
+}
+
+fn successor<T>(node: BinaryNodeRef<'_, T>) -> Option<BinaryNodeRef<'_, T>> {
+    if let Some(right) = node.right() {
+        return Some(bst::leftmost(right));
+    }
+    let mut node = node;
+    while node.is_right_child() {
+        node = node.parent().expect("exists");
+    }
+    node.parent()
+}
+
+fn predecessor<T>(node: BinaryNodeRef<'_, T>) -> Option<BinaryNodeRef<'_, T>> {
+    if let Some(left) = node.left() {
+        return Some(bst::rightmost(left));
+    }
+    let mut node = node;
+    while node.is_left_child() {
+        node = node.parent().expect("exists");
+    }
+    node.parent()
+}
+
+/// Empty slots count as black.
+fn is_red<K, V>(node: Option<BinaryNodeRef<'_, RbNode<K, V>>>) -> bool {
+    node.is_some_and(|node| node.value().color == Color::Red)
+}
+
+fn color<K, V>(tree: &Tree<K, V>, id: Option<BinaryNodeId>) -> Color {
+    match id.and_then(|id| tree.get(id)) {
+        Some(node) => node.value().color,
+        None => Color::Black,
+    }
+}
+
+fn set_color<K, V>(tree: &mut Tree<K, V>, id: BinaryNodeId, color: Color) {
+    tree.get_mut(id).expect("exists").value().color = color;
+}
+
+fn parent_id<T>(tree: &BinaryTree<T>, id: BinaryNodeId) -> Option<BinaryNodeId> {
+    tree.get(id)?.parent().map(|parent| parent.id())
+}
+
+fn left_id<T>(tree: &BinaryTree<T>, id: BinaryNodeId) -> Option<BinaryNodeId> {
+    tree.get(id)?.left().map(|left| left.id())
+}
+
+fn right_id<T>(tree: &BinaryTree<T>, id: BinaryNodeId) -> Option<BinaryNodeId> {
+    tree.get(id)?.right().map(|right| right.id())
+}
+
+fn rotate_left<T>(tree: &mut BinaryTree<T>, id: BinaryNodeId) {
+    tree.get_mut(id).expect("exists").rotate_left();
+}
+
+fn rotate_right<T>(tree: &mut BinaryTree<T>, id: BinaryNodeId) {
+    tree.get_mut(id).expect("exists").rotate_right();
+}
+
+/// Restores the red-black invariants after inserting the red node `id`.
+fn insert_fixup<K, V>(tree: &mut Tree<K, V>, mut id: BinaryNodeId) {
+    while let Some(mut parent) = parent_id(tree, id) {
+        if color(tree, Some(parent)) == Color::Black {
+            break;
+        }
+        // A red parent is never the root, so the grandparent exists.
+        let grandparent = parent_id(tree, parent).expect("exists");
+        let parent_is_left = left_id(tree, grandparent) == Some(parent);
+        let uncle = if parent_is_left {
+            right_id(tree, grandparent)
+        } else {
+            left_id(tree, grandparent)
+        };
+
+        if let (Some(uncle), Color::Red) = (uncle, color(tree, uncle)) {
+            set_color(tree, parent, Color::Black);
+            set_color(tree, uncle, Color::Black);
+            set_color(tree, grandparent, Color::Red);
+            id = grandparent;
+            continue;
+        }
+
+        if parent_is_left {
+            if right_id(tree, parent) == Some(id) {
+                rotate_left(tree, parent);
+                id = parent;
+                parent = parent_id(tree, id).expect("exists");
+            }
+            set_color(tree, parent, Color::Black);
+            set_color(tree, grandparent, Color::Red);
+            rotate_right(tree, grandparent);
+        } else {
+            if left_id(tree, parent) == Some(id) {
+                rotate_right(tree, parent);
+                id = parent;
+                parent = parent_id(tree, id).expect("exists");
+            }
+            set_color(tree, parent, Color::Black);
+            set_color(tree, grandparent, Color::Red);
+            rotate_left(tree, grandparent);
+        }
+    }
+
+    let root_id = tree.root().id();
+    set_color(tree, root_id, Color::Black);
+}
+
+/// Restores the red-black invariants after a black node was removed from
+/// the `is_left` slot of `parent`, leaving `node` (possibly empty) in its
+/// place with one black too few on its paths.
+fn remove_fixup<K, V>(
+    tree: &mut Tree<K, V>,
+    mut node: Option<BinaryNodeId>,
+    mut parent: Option<BinaryNodeId>,
+    mut is_left: bool,
+) {
+    while color(tree, node) == Color::Black {
+        let current_parent = match parent {
+            Some(parent) => parent,
+            None => break,
+        };
+        // The sibling subtree contains at least one black node, so it is
+        // never empty.
+        let sibling_of = |tree: &Tree<K, V>| {
+            if is_left {
+                right_id(tree, current_parent)
+            } else {
+                left_id(tree, current_parent)
+            }
+            .expect("exists")
+        };
+
+        let mut sibling = sibling_of(tree);
+        if color(tree, Some(sibling)) == Color::Red {
+            set_color(tree, sibling, Color::Black);
+            set_color(tree, current_parent, Color::Red);
+            if is_left {
+                rotate_left(tree, current_parent);
+            } else {
+                rotate_right(tree, current_parent);
+            }
+            sibling = sibling_of(tree);
+        }
+
+        let (near, far) = if is_left {
+            (left_id(tree, sibling), right_id(tree, sibling))
+        } else {
+            (right_id(tree, sibling), left_id(tree, sibling))
+        };
+        if color(tree, near) == Color::Black && color(tree, far) == Color::Black {
+            set_color(tree, sibling, Color::Red);
+            node = Some(current_parent);
+            is_left = tree.get(current_parent).expect("exists").is_left_child();
+            parent = parent_id(tree, current_parent);
+            continue;
+        }
+
+        if color(tree, far) == Color::Black {
+            set_color(tree, near.expect("red near nephew"), Color::Black);
+            set_color(tree, sibling, Color::Red);
+            if is_left {
+                rotate_right(tree, sibling);
+            } else {
+                rotate_left(tree, sibling);
+            }
+            sibling = sibling_of(tree);
+        }
+
+        let far = if is_left {
+            right_id(tree, sibling)
+        } else {
+            left_id(tree, sibling)
+        };
+        set_color(tree, sibling, color(tree, Some(current_parent)));
+        set_color(tree, current_parent, Color::Black);
+        set_color(tree, far.expect("red far nephew"), Color::Black);
+        if is_left {
+            rotate_left(tree, current_parent);
+        } else {
+            rotate_right(tree, current_parent);
+        }
+        break;
+    }
+
+    if let Some(node) = node {
+        set_color(tree, node, Color::Black);
+    }
+    let root_id = tree.root().id();
+    set_color(tree, root_id, Color::Black);
+}
+
+/// Iterator over the entries of an `RbTreeMap` in ascending key order.
+pub struct Iter<'a, K, V> {
+    inner: Option<Inorder<'a, RbNode<K, V>>>,
+}
+
+impl<'a, K, V> Clone for Iter<'a, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.as_mut()?.next().map(entry)
+    }
+}
+
+/// Iterator over a range of entries of an `RbTreeMap` in ascending key order.
+pub struct Range<'a, K, V> {
+    next: Option<BinaryNodeRef<'a, RbNode<K, V>>>,
+    last: Option<BinaryNodeRef<'a, RbNode<K, V>>>,
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next?;
+        self.next = if Some(node) == self.last {
+            None
+        } else {
+            successor(node)
+        };
+        Some(entry(node))
+    }
+}
+
+/// Ordered set backed by a red-black tree.
+pub struct RbTreeSet<K> {
+    map: RbTreeMap<K, ()>,
+}
+
+impl<K: Ord> RbTreeSet<K> {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self {
+            map: RbTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of keys in the set.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if the set contains no keys.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Adds a key to the set, returning true if it was not already present.
+    pub fn insert(&mut self, key: K) -> bool {
+        self.map.insert(key, ()).is_none()
+    }
+
+    /// Returns true if the set contains the key.
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Removes a key from the set, returning true if it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove(key).is_some()
+    }
+
+    /// Returns the smallest key.
+    pub fn first(&self) -> Option<&K> {
+        self.map.first_key_value().map(|(key, _)| key)
+    }
+
+    /// Returns the largest key.
+    pub fn last(&self) -> Option<&K> {
+        self.map.last_key_value().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the keys in ascending order.
+    pub fn iter(&self) -> SetIter<'_, K> {
+        SetIter {
+            inner: self.map.iter(),
+        }
+    }
+
+    /// Returns an iterator over the keys that fall in `range`, in ascending
+    /// order.
+    pub fn range<Q, R>(&self, range: R) -> SetRange<'_, K>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        SetRange {
+            inner: self.map.range(range),
+        }
+    }
+
+    /// Checks the red-black invariants, see `RbTreeMap::validate`.
+    pub fn validate(&self) -> Result<(), String> {
+        self.map.validate()
+    }
+}
+
+impl<K: Ord> Default for RbTreeSet<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, K: Ord> IntoIterator for &'a RbTreeSet<K> {
+    type Item = &'a K;
+    type IntoIter = SetIter<'a, K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the keys of an `RbTreeSet` in ascending order.
+pub struct SetIter<'a, K> {
+    inner: Iter<'a, K, ()>,
+}
+
+impl<'a, K> Clone for SetIter<'a, K> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K> Iterator for SetIter<'a, K> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+}
+
+/// Iterator over a range of keys of an `RbTreeSet` in ascending order.
+pub struct SetRange<'a, K> {
+    inner: Range<'a, K, ()>,
+}
+
+impl<'a, K> Iterator for SetRange<'a, K> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inserts_and_removals_keep_invariants() {
+        let mut map = RbTreeMap::new();
+        for i in 0..1000 {
+            let key = (i * 389) % 1000;
+            assert_eq!(map.insert(key, key), None);
+            map.validate().unwrap();
+        }
+        assert_eq!(map.len(), 1000);
+        assert!(map.tree.as_ref().unwrap().height() < 20);
+        assert!(map.iter().map(|(key, _)| *key).eq(0..1000));
+
+        for key in (0..1000).filter(|key| key % 4 != 1) {
+            assert_eq!(map.remove(&key), Some(key));
+            map.validate().unwrap();
+        }
+        assert_eq!(map.remove(&0), None);
+        assert!(map.iter().map(|(key, _)| *key).eq((1..1000).step_by(4)));
+
+        for key in (1..1000).step_by(4).rev() {
+            assert_eq!(map.remove(&key), Some(key));
+            map.validate().unwrap();
+        }
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn sorted_inserts_keep_invariants() {
+        let mut map = RbTreeMap::new();
+        for key in 0..500 {
+            map.insert(key, ());
+            map.validate().unwrap();
+        }
+        assert_eq!(map.first_key_value(), Some((&0, &())));
+        assert_eq!(map.last_key_value(), Some((&499, &())));
+    }
+
+    #[test]
+    fn range_works() {
+        let map: RbTreeMap<i32, i32> = {
+            let mut map = RbTreeMap::new();
+            for key in (0..20).step_by(2) {
+                map.insert(key, key * 10);
+            }
+            map
+        };
+        let keys = |range: Range<'_, i32, i32>| range.map(|(key, _)| *key).collect::<Vec<_>>();
+
+        assert_eq!(keys(map.range(3..9)), vec![4, 6, 8]);
+        assert_eq!(keys(map.range(4..=8)), vec![4, 6, 8]);
+        assert_eq!(
+            keys(map.range((Bound::Excluded(4), Bound::Excluded(8)))),
+            vec![6]
+        );
+        assert_eq!(keys(map.range(..3)), vec![0, 2]);
+        assert_eq!(keys(map.range(15..)), vec![16, 18]);
+        assert_eq!(keys(map.range(..)).len(), 10);
+        assert!(keys(map.range(5..5)).is_empty());
+        assert!(keys(map.range(30..)).is_empty());
+    }
+
+    #[test]
+    fn entry_api_works() {
+        let mut map = RbTreeMap::new();
+        for word in "a b a c b a".split(' ') {
+            *map.entry(word).or_insert(0) += 1;
+        }
+        assert_eq!(map.get("a"), Some(&3));
+        assert_eq!(map.get("c"), Some(&1));
+
+        map.entry("c").and_modify(|count| *count += 10).or_default();
+        map.entry("d").and_modify(|count| *count += 10).or_default();
+        assert_eq!(map.get("c"), Some(&11));
+        assert_eq!(map.get("d"), Some(&0));
+
+        match map.entry("b") {
+            Entry::Occupied(entry) => assert_eq!(entry.remove_entry(), ("b", 2)),
+            Entry::Vacant(_) => unreachable!(),
+        }
+        assert!(!map.contains_key("b"));
+        map.validate().unwrap();
+    }
+
+    #[test]
+    fn churn_does_not_grow_arena() {
+        let mut map = RbTreeMap::new();
+        bst::check_churn(
+            &mut map,
+            |map, key| {
+                map.insert(key, ());
+            },
+            |map, key| {
+                map.remove(&key);
+            },
+            |map| map.tree.as_ref().unwrap().arena_len(),
+        );
+        map.validate().unwrap();
+        assert_eq!(map.len(), 16);
+    }
+
+    #[test]
+    fn set_works() {
+        let mut set = RbTreeSet::new();
+        for key in &[5, 1, 4, 2, 3] {
+            assert!(set.insert(*key));
+        }
+        assert!(!set.insert(3));
+        assert!(set.remove(&4));
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 5]);
+        assert_eq!(set.range(2..).copied().collect::<Vec<_>>(), vec![2, 3, 5]);
+        assert_eq!((set.first(), set.last()), (Some(&1), Some(&5)));
+        set.validate().unwrap();
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+
+use crate::binary_tree::BinaryTree;
+
+/// Error returned when a tree can't be rebuilt from its traversals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraversalError {
+    /// The sequences are empty, a tree has at least a root.
+    Empty,
+    /// The sequences have different lengths.
+    LengthMismatch { order: usize, inorder: usize },
+    /// The value at `index` of the in-order sequence occurs earlier in it too.
+    DuplicateValue { index: usize },
+    /// The value at `index` of the pre-order / post-order sequence doesn't
+    /// occur in the in-order sequence.
+    UnknownValue { index: usize },
+    /// The value at `index` of the pre-order / post-order sequence can't be
+    /// the root of its subtree given the in-order sequence.
+    Inconsistent { index: usize },
+}
+
+impl Display for TraversalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TraversalError::Empty => write!(f, "traversals are empty"),
+            TraversalError::LengthMismatch { order, inorder } => write!(
+                f,
+                "traversal has {} values but in-order has {}",
+                order, inorder
+            ),
+            TraversalError::DuplicateValue { index } => {
+                write!(f, "in-order value at {} is a duplicate", index)
+            }
+            TraversalError::UnknownValue { index } => {
+                write!(f, "traversal value at {} is missing from in-order", index)
+            }
+            TraversalError::Inconsistent { index } => write!(
+                f,
+                "traversal value at {} is out of place for the in-order",
+                index
+            ),
+        }
+    }
+}
+
+impl Error for TraversalError {}
+
+impl<T: Clone + Eq + Hash> BinaryTree<T> {
+    /// Rebuilds the tree with the given pre-order and in-order traversals.
+    /// Values must be distinct, so that the tree is unique.
+    pub fn from_preorder_inorder(preorder: &[T], inorder: &[T]) -> Result<Self, TraversalError> {
+        rebuild(preorder, inorder, Order::Pre)
+    }
+
+    /// Rebuilds the tree with the given post-order and in-order traversals.
+    /// Values must be distinct, so that the tree is unique.
+    pub fn from_postorder_inorder(postorder: &[T], inorder: &[T]) -> Result<Self, TraversalError> {
+        rebuild(postorder, inorder, Order::Post)
+    }
+}
+
+impl<T> BinaryTree<T> {
+    /// Builds a minimal-height tree whose in-order traversal yields the values
+    /// in the given order, in O(n). Returns `None` if there are no values.
+    ///
+    /// Every subtree is rooted at the middle of its values, so the tree is
+    /// height-balanced and its values are in search order when the input is
+    /// sorted.
+    pub fn from_sorted<I: IntoIterator<Item = T>>(values: I) -> Option<Self> {
+        let mut values: Vec<Option<T>> = values.into_iter().map(Some).collect();
+        let len = values.len();
+        if len == 0 {
+            return None;
+        }
+        let mut take = |index: usize| values[index].take().expect("taken once");
+
+        // Each entry is a node with the half-open ranges of the values left
+        // for its left and right subtrees.
+        let middle = len / 2;
+        let mut tree = BinaryTree::new(take(middle));
+        let mut stack = vec![(tree.root().id(), 0..middle, middle + 1..len)];
+        while let Some((id, left, right)) = stack.pop() {
+            let mut node = tree.get_mut(id).expect("exists");
+            if !left.is_empty() {
+                let middle = left.start + left.len() / 2;
+                let left_id = node.set_left(take(middle)).id();
+                stack.push((left_id, left.start..middle, middle + 1..left.end));
+            }
+            if !right.is_empty() {
+                let middle = right.start + right.len() / 2;
+                let right_id = node.set_right(take(middle)).id();
+                stack.push((right_id, right.start..middle, middle + 1..right.end));
+            }
+        }
+        Some(tree)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Order {
+    Pre,
+    Post,
+}
+
+/// Subtree still to be built: it covers `len` values starting at `start` in
+/// the pre-order / post-order sequence and at `in_start` in the in-order one.
+struct Span {
+    start: usize,
+    in_start: usize,
+    len: usize,
+}
+
+fn rebuild<T: Clone + Eq + Hash>(
+    order: &[T],
+    inorder: &[T],
+    kind: Order,
+) -> Result<BinaryTree<T>, TraversalError> {
+    if order.len() != inorder.len() {
+        return Err(TraversalError::LengthMismatch {
+            order: order.len(),
+            inorder: inorder.len(),
+        });
+    }
+    if order.is_empty() {
+        return Err(TraversalError::Empty);
+    }
+
+    let mut positions = HashMap::with_capacity(inorder.len());
+    for (index, value) in inorder.iter().enumerate() {
+        if positions.insert(value, index).is_some() {
+            return Err(TraversalError::DuplicateValue { index });
+        }
+    }
+
+    // Splits a span into the index of its root in `order` and the spans of
+    // its left and right subtrees.
+    let split = |span: &Span| -> Result<(usize, Span, Span), TraversalError> {
+        let (root, left_start) = match kind {
+            Order::Pre => (span.start, span.start + 1),
+            Order::Post => (span.start + span.len - 1, span.start),
+        };
+        let position = *positions
+            .get(&order[root])
+            .ok_or(TraversalError::UnknownValue { index: root })?;
+        if position < span.in_start || position >= span.in_start + span.len {
+            return Err(TraversalError::Inconsistent { index: root });
+        }
+
+        let left_len = position - span.in_start;
+        let left = Span {
+            start: left_start,
+            in_start: span.in_start,
+            len: left_len,
+        };
+        let right = Span {
+            start: left_start + left_len,
+            in_start: position + 1,
+            len: span.len - left_len - 1,
+        };
+        Ok((root, left, right))
+    };
+
+    let (root, left, right) = split(&Span {
+        start: 0,
+        in_start: 0,
+        len: order.len(),
+    })?;
+    let mut tree = BinaryTree::new(order[root].clone());
+
+    let root_id = tree.root().id();
+    let mut stack = vec![(root_id, left, right)];
+    while let Some((id, left, right)) = stack.pop() {
+        let mut node = tree.get_mut(id).expect("exists");
+        if left.len > 0 {
+            let (root, grand_left, grand_right) = split(&left)?;
+            let left_id = node.set_left(order[root].clone()).id();
+            stack.push((left_id, grand_left, grand_right));
+        }
+        if right.len > 0 {
+            let (root, grand_left, grand_right) = split(&right)?;
+            let right_id = node.set_right(order[root].clone()).id();
+            stack.push((right_id, grand_left, grand_right));
+        }
+    }
+    Ok(tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebuild_from_traversals_works() {
+        let tree = binary_tree! {
+            3 => {
+                left: 9,
+                right: 20 => {
+                    left: 15,
+                    right: 7 => {
+                        left: 1,
+                    },
+                },
+            }
+        };
+        let preorder: Vec<_> = tree.preorder().map(|node| *node.value()).collect();
+        let inorder: Vec<_> = tree.inorder().map(|node| *node.value()).collect();
+        let postorder: Vec<_> = tree.postorder().map(|node| *node.value()).collect();
+
+        let rebuilt = BinaryTree::from_preorder_inorder(&preorder, &inorder).unwrap();
+        assert_eq!(format!("{:?}", rebuilt), format!("{:?}", tree));
+        let rebuilt = BinaryTree::from_postorder_inorder(&postorder, &inorder).unwrap();
+        assert_eq!(format!("{:?}", rebuilt), format!("{:?}", tree));
+    }
+
+    #[test]
+    fn from_sorted_builds_balanced_tree() {
+        for len in 1..=32usize {
+            let tree = BinaryTree::from_sorted(0..len).unwrap();
+            let inorder: Vec<_> = tree.inorder().map(|node| *node.value()).collect();
+            assert_eq!(inorder, (0..len).collect::<Vec<_>>());
+            assert_eq!(tree.height(), len.ilog2() as usize);
+            assert!(tree.is_height_balanced());
+        }
+
+        assert!(BinaryTree::<i32>::from_sorted(vec![]).is_none());
+    }
+
+    #[test]
+    fn inconsistent_traversals_are_rejected() {
+        assert_eq!(
+            BinaryTree::<i32>::from_preorder_inorder(&[], &[]).unwrap_err(),
+            TraversalError::Empty
+        );
+        assert_eq!(
+            BinaryTree::from_preorder_inorder(&[1, 2], &[1]).unwrap_err(),
+            TraversalError::LengthMismatch {
+                order: 2,
+                inorder: 1
+            }
+        );
+        assert_eq!(
+            BinaryTree::from_preorder_inorder(&[1, 2], &[2, 2]).unwrap_err(),
+            TraversalError::DuplicateValue { index: 1 }
+        );
+        assert_eq!(
+            BinaryTree::from_postorder_inorder(&[1, 4], &[1, 2]).unwrap_err(),
+            TraversalError::UnknownValue { index: 1 }
+        );
+        // 1 is the root with 2 on its left, so the pre-order must continue
+        // with 2 rather than 3.
+        assert_eq!(
+            BinaryTree::from_preorder_inorder(&[1, 3, 2], &[2, 1, 3]).unwrap_err(),
+            TraversalError::Inconsistent { index: 1 }
+        );
+    }
+}
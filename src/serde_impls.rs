@@ -0,0 +1,122 @@
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::binary_tree::{BinaryNodeRef, BinaryTree};
+
+/// Serializes the tree as nested `{ "value": ..., "left": ..., "right": ... }`
+/// structs, with `null` for absent children.
+impl<T: Serialize> Serialize for BinaryTree<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeNode(self.root()).serialize(serializer)
+    }
+}
+
+struct SerializeNode<'a, T>(BinaryNodeRef<'a, T>);
+
+impl<'a, T: Serialize> Serialize for SerializeNode<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut node = serializer.serialize_struct("BinaryTree", 3)?;
+        node.serialize_field("value", self.0.value())?;
+        node.serialize_field("left", &self.0.left().map(SerializeNode))?;
+        node.serialize_field("right", &self.0.right().map(SerializeNode))?;
+        node.end()
+    }
+}
+
+/// Deserializes the representation written by `Serialize`. Absent children
+/// may be `null` or left out.
+///
+/// Every level of the tree is a level of nesting in the input, so deep trees
+/// can exceed the deserializer's recursion limit. `serde_json` stops at 128
+/// levels; for deeper trees build the `serde_json::Deserializer` yourself and
+/// call `disable_recursion_limit` on it (behind its `unbounded_depth`
+/// feature), keeping in mind that nesting then only stops at the end of the
+/// stack.
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for BinaryTree<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        OwnedNode::deserialize(deserializer).map(OwnedNode::into_tree)
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename = "BinaryTree")]
+struct OwnedNode<T> {
+    value: T,
+    left: Option<Box<OwnedNode<T>>>,
+    right: Option<Box<OwnedNode<T>>>,
+}
+
+impl<T> OwnedNode<T> {
+    fn into_tree(self) -> BinaryTree<T> {
+        let mut tree = BinaryTree::new(self.value);
+        let mut stack = vec![(tree.root().id(), self.left, self.right)];
+        while let Some((id, left, right)) = stack.pop() {
+            let mut node = tree.get_mut(id).expect("exists");
+            if let Some(left) = left {
+                let left = *left;
+                let left_id = node.set_left(left.value).id();
+                stack.push((left_id, left.left, left.right));
+            }
+            if let Some(right) = right {
+                let right = *right;
+                let right_id = node.set_right(right.value).id();
+                stack.push((right_id, right.left, right.right));
+            }
+        }
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use crate::BinaryTree;
+
+    #[test]
+    fn json_round_trip_works() {
+        let tree = binary_tree! {
+            1 => {
+                right: 2 => {
+                    left: 3,
+                },
+            }
+        };
+
+        let json = serde_json::to_string(&tree).unwrap();
+        assert_eq!(
+            json,
+            r#"{"value":1,"left":null,"right":{"value":2,"left":{"value":3,"left":null,"right":null},"right":null}}"#
+        );
+
+        let parsed: BinaryTree<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(format!("{:?}", parsed), format!("{:?}", tree));
+        assert!(parsed.root().left().is_none());
+    }
+
+    #[test]
+    fn deep_trees_need_the_recursion_limit_disabled() {
+        let mut tree = BinaryTree::new(0);
+        let mut id = tree.root().id();
+        for value in 1..200 {
+            id = tree.get_mut(id).unwrap().set_right(value).id();
+        }
+        assert_eq!(tree.height(), 199);
+
+        let json = serde_json::to_string(&tree).unwrap();
+        let error = serde_json::from_str::<BinaryTree<i32>>(&json).unwrap_err();
+        assert!(error.to_string().contains("recursion limit exceeded"));
+
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        deserializer.disable_recursion_limit();
+        let parsed = BinaryTree::<i32>::deserialize(&mut deserializer).unwrap();
+        assert_eq!(parsed, tree);
+    }
+
+    #[test]
+    fn missing_children_deserialize_as_absent() {
+        let parsed: BinaryTree<String> =
+            serde_json::from_str(r#"{"value":"root","right":{"value":"r"}}"#).unwrap();
+        assert_eq!(format!("{:?}", parsed), r#""root" => { right: "r" }"#);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::binary_tree::{BinaryNodeRef, BinaryTree};
+
+// A tree always contains at least one node, so there is no `is_empty`.
+#[allow(clippy::len_without_is_empty)]
+impl<T> BinaryTree<T> {
+    /// Returns the number of nodes in the tree.
+    pub fn len(&self) -> usize {
+        self.root().len()
+    }
+
+    /// Returns the number of edges on the longest path from the root to a
+    /// leaf. A tree with only a root has height 0.
+    pub fn height(&self) -> usize {
+        self.root().height()
+    }
+
+    /// Returns the number of nodes without children.
+    pub fn leaf_count(&self) -> usize {
+        self.root().leaf_count()
+    }
+
+    /// Returns true if every node has either zero or two children.
+    pub fn is_full(&self) -> bool {
+        self.root().is_full()
+    }
+
+    /// Returns true if every level except possibly the last is completely
+    /// filled, and the last level is filled from the left.
+    pub fn is_complete(&self) -> bool {
+        self.root().is_complete()
+    }
+
+    /// Returns true if every level is completely filled.
+    pub fn is_perfect(&self) -> bool {
+        self.root().is_perfect()
+    }
+
+    /// Returns true if the heights of the two subtrees of every node differ by
+    /// at most one.
+    pub fn is_height_balanced(&self) -> bool {
+        self.root().is_height_balanced()
+    }
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl<'a, T> BinaryNodeRef<'a, T> {
+    /// Returns the number of nodes in this subtree, including this node.
+    pub fn len(&self) -> usize {
+        self.preorder().count()
+    }
+
+    /// Returns the number of edges on the longest path from this node to a
+    /// leaf. A leaf has height 0.
+    pub fn height(&self) -> usize {
+        self.levels().count() - 1
+    }
+
+    /// Returns the number of edges from the root of the tree to this node.
+    pub fn depth(&self) -> usize {
+        self.ancestors().count()
+    }
+
+    /// Returns the number of leaves in this subtree.
+    pub fn leaf_count(&self) -> usize {
+        self.preorder().filter(BinaryNodeRef::is_leaf).count()
+    }
+
+    /// Returns true if this node has no children.
+    pub fn is_leaf(&self) -> bool {
+        self.left().is_none() && self.right().is_none()
+    }
+
+    /// Returns true if every node in this subtree has zero or two children.
+    pub fn is_full(&self) -> bool {
+        self.preorder()
+            .all(|node| node.left().is_some() == node.right().is_some())
+    }
+
+    /// Returns true if every level of this subtree except possibly the last is
+    /// completely filled, and the last level is filled from the left.
+    pub fn is_complete(&self) -> bool {
+        let mut seen_empty_slot = false;
+        for node in self.level_order() {
+            for child in [node.left(), node.right()].iter() {
+                match child {
+                    Some(_) if seen_empty_slot => return false,
+                    Some(_) => {}
+                    None => seen_empty_slot = true,
+                }
+            }
+        }
+        true
+    }
+
+    /// Returns true if every level of this subtree is completely filled.
+    pub fn is_perfect(&self) -> bool {
+        self.levels()
+            .enumerate()
+            .all(|(depth, level)| level.len() == 1 << depth)
+    }
+
+    /// Returns true if the heights of the two subtrees of every node in this
+    /// subtree differ by at most one.
+    pub fn is_height_balanced(&self) -> bool {
+        // Heights of already visited subtrees, with -1 for an empty subtree.
+        // In post-order the children of a node are on top of the stack.
+        let mut heights: Vec<isize> = Vec::new();
+        for node in self.postorder() {
+            let right = if node.right().is_some() {
+                heights.pop().expect("visited")
+            } else {
+                -1
+            };
+            let left = if node.left().is_some() {
+                heights.pop().expect("visited")
+            } else {
+                -1
+            };
+
+            if (left - right).abs() > 1 {
+                return false;
+            }
+            heights.push(1 + left.max(right));
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn counts_and_heights_work() {
+        let tree = binary_tree! {
+            1 => {
+                left: 2 => {
+                    left: 4,
+                    right: 5,
+                },
+                right: 3 => {
+                    right: 6 => {
+                        left: 7,
+                    },
+                },
+            }
+        };
+
+        assert_eq!(tree.len(), 7);
+        assert_eq!(tree.height(), 3);
+        assert_eq!(tree.leaf_count(), 3);
+
+        let six = tree.root().right().unwrap().right().unwrap();
+        assert_eq!(six.len(), 2);
+        assert_eq!(six.height(), 1);
+        assert_eq!(six.depth(), 2);
+        assert!(!six.is_leaf());
+        assert!(six.left().unwrap().is_leaf());
+        assert_eq!(tree.root().depth(), 0);
+    }
+
+    #[test]
+    fn shape_predicates_work() {
+        let perfect = binary_tree! {
+            1 => {
+                left: 2 => { left: 4, right: 5 },
+                right: 3 => { left: 6, right: 7 },
+            }
+        };
+        assert!(perfect.is_full());
+        assert!(perfect.is_complete());
+        assert!(perfect.is_perfect());
+        assert!(perfect.is_height_balanced());
+
+        let complete = binary_tree! {
+            1 => {
+                left: 2 => { left: 4 },
+                right: 3,
+            }
+        };
+        assert!(!complete.is_full());
+        assert!(complete.is_complete());
+        assert!(!complete.is_perfect());
+        assert!(complete.is_height_balanced());
+
+        let gap = binary_tree! {
+            1 => {
+                left: 2 => { right: 5 },
+                right: 3,
+            }
+        };
+        assert!(!gap.is_complete());
+        assert!(gap.is_height_balanced());
+
+        let chain = binary_tree! {
+            1 => {
+                right: 2 => {
+                    right: 3,
+                },
+            }
+        };
+        assert!(!chain.is_full());
+        assert!(!chain.is_complete());
+        assert!(!chain.is_height_balanced());
+        assert!(binary_tree!(1).is_perfect());
+    }
+}
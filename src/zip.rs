@@ -0,0 +1,189 @@
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::iter;
+
+use crate::binary_tree::{BinaryNodeRef, BinaryTree, Side};
+use crate::map::{build, shape};
+
+/// Error returned when two trees which must have the same shape don't.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapeMismatch {
+    path: Vec<Side>,
+}
+
+impl ShapeMismatch {
+    /// Returns the path from the root to the first child slot, in pre-order,
+    /// which is occupied in one tree and empty in the other.
+    pub fn path(&self) -> &[Side] {
+        &self.path
+    }
+}
+
+impl Display for ShapeMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("trees differ in shape at root")?;
+        for side in &self.path {
+            write!(f, "/{}", side)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ShapeMismatch {}
+
+impl<T> BinaryTree<T> {
+    /// Pairs up the values of two trees with the same shape.
+    pub fn zip<'a, U>(
+        &'a self,
+        other: &'a BinaryTree<U>,
+    ) -> Result<BinaryTree<(&'a T, &'a U)>, ShapeMismatch> {
+        self.zip_with(other, |value, other_value| (value, other_value))
+    }
+
+    /// Combines the values of two trees with the same shape with `f`, in
+    /// pre-order. `f` isn't called if the shapes differ.
+    pub fn zip_with<'a, U, V, F>(
+        &'a self,
+        other: &'a BinaryTree<U>,
+        mut f: F,
+    ) -> Result<BinaryTree<V>, ShapeMismatch>
+    where
+        F: FnMut(&'a T, &'a U) -> V,
+    {
+        if let Some(mismatch) = first_mismatch(self.root(), other.root()) {
+            return Err(mismatch);
+        }
+
+        // With the shapes equal, both trees list their nodes in the same
+        // order and `build` visits every one of them.
+        let mut other_nodes = shape(other).into_iter();
+        let built = build(&shape(self), |id| {
+            let (other_id, _) = other_nodes.next().expect("same shape");
+            let value = self.get(id).expect("exists").value();
+            let other_value = other.get(other_id).expect("exists").value();
+            Ok::<_, Infallible>(Some(f(value, other_value)))
+        });
+        match built {
+            Ok(tree) => Ok(tree.expect("root is kept")),
+            Err(never) => match never {},
+        }
+    }
+}
+
+/// Returns the first child slot, in pre-order, which is occupied below one
+/// node and empty below the other.
+pub(crate) fn first_mismatch<T, U>(
+    root: BinaryNodeRef<'_, T>,
+    other_root: BinaryNodeRef<'_, U>,
+) -> Option<ShapeMismatch> {
+    let mut stack = vec![(root, other_root)];
+    while let Some((node, other)) = stack.pop() {
+        let slots = [
+            (Side::Left, node.left(), other.left()),
+            (Side::Right, node.right(), other.right()),
+        ];
+        for &(side, child, other_child) in slots.iter() {
+            if child.is_some() != other_child.is_some() {
+                return Some(ShapeMismatch {
+                    path: path_to(root, node, side),
+                });
+            }
+        }
+
+        // Push right first so the left subtrees are compared first.
+        stack.extend(node.right().zip(other.right()));
+        stack.extend(node.left().zip(other.left()));
+    }
+    None
+}
+
+/// Returns the path from `root` down to the child slot on `side` of `node`.
+fn path_to<T>(root: BinaryNodeRef<'_, T>, node: BinaryNodeRef<'_, T>, side: Side) -> Vec<Side> {
+    let mut path: Vec<Side> = iter::successors(Some(node), |node| node.parent())
+        .take_while(|node| *node != root)
+        .map(|node| {
+            if node.is_left_child() {
+                Side::Left
+            } else {
+                Side::Right
+            }
+        })
+        .collect();
+    path.reverse();
+    path.push(side);
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zip_works() {
+        let tree = binary_tree! {
+            "+" => {
+                left: "x",
+                right: "y",
+            }
+        };
+        let costs = binary_tree! {
+            3 => {
+                left: 1,
+                right: 2,
+            }
+        };
+
+        let zipped = tree.zip(&costs).unwrap();
+        assert_eq!(
+            format!("{:?}", zipped),
+            r#"("+", 3) => { left: ("x", 1), right: ("y", 2) }"#
+        );
+
+        let labels = tree
+            .zip_with(&costs, |op, cost| format!("{}:{}", op, cost))
+            .unwrap();
+        assert_eq!(
+            format!("{:?}", labels),
+            r#""+:3" => { left: "x:1", right: "y:2" }"#
+        );
+    }
+
+    #[test]
+    fn shape_mismatch_reports_path() {
+        let tree = binary_tree! {
+            1 => {
+                left: 2 => {
+                    right: 4,
+                },
+                right: 3,
+            }
+        };
+        let other = binary_tree! {
+            1 => {
+                left: 2 => {
+                    left: 4,
+                },
+            }
+        };
+
+        let mut called = false;
+        let mismatch = tree.zip_with(&other, |_, _| called = true).unwrap_err();
+        assert!(!called);
+        assert_eq!(mismatch.path(), &[Side::Right]);
+
+        // Paths are relative to the nodes being compared.
+        let left = tree.root().left().unwrap();
+        let mismatch = first_mismatch(left, other.root().left().unwrap()).unwrap();
+        assert_eq!(mismatch.path(), &[Side::Left]);
+
+        let mut shorter = binary_tree!(1 => { left: 2 => { left: 4 } });
+        shorter.root_mut().set_right(3);
+        let mismatch = tree.zip(&shorter).unwrap_err();
+        assert_eq!(mismatch.path(), &[Side::Left, Side::Left]);
+        assert_eq!(
+            mismatch.to_string(),
+            "trees differ in shape at root/left/left"
+        );
+    }
+}